@@ -1,12 +1,13 @@
 use criterion::*;
 
-use prodash::{messages::MessageLevel, Tree, TreeOptions};
+use prodash::{messages::MessageLevel, progress::Changes, Tree, TreeOptions};
 
 fn usage(c: &mut Criterion) {
     fn small_tree() -> Tree {
         TreeOptions {
             initial_capacity: 10,
             message_buffer_capacity: 2,
+            ..TreeOptions::default()
         }
         .create()
     }
@@ -35,6 +36,33 @@ fn usage(c: &mut Criterion) {
                 progress.set(5);
             });
         });
+    c.benchmark_group("tree::Item::inc")
+        .throughput(Throughput::Elements(5))
+        .bench_function("inc tree 5 times", |b| {
+            let root = small_tree();
+            let mut progress = root.add_child("the one");
+            progress.init(Some(20), Some("element".into()));
+            b.iter(|| {
+                progress.inc();
+                progress.inc();
+                progress.inc();
+                progress.inc();
+                progress.inc();
+            });
+        })
+        .bench_function("inc shared counter 5 times", |b| {
+            let root = small_tree();
+            let mut progress = root.add_child("the one");
+            progress.init(Some(20), Some("element".into()));
+            let counter = progress.counter();
+            b.iter(|| {
+                counter.inc();
+                counter.inc();
+                counter.inc();
+                counter.inc();
+                counter.inc();
+            });
+        });
     c.benchmark_group("tree::Item::message")
         .throughput(Throughput::Elements(1))
         .bench_function(
@@ -61,6 +89,30 @@ fn usage(c: &mut Criterion) {
                 root.copy_messages(&mut out);
             });
         });
+    c.benchmark_group("Tree::snapshot")
+        .throughput(Throughput::Elements(20_000))
+        .bench_function("sorted snapshot of 20k tasks", |b| {
+            let root = Tree::new();
+            let _tasks: Vec<_> = (0..20_000).map(|_| root.add_child("task")).collect();
+            let mut out = Vec::new();
+            b.iter(|| {
+                root.sorted_snapshot(&mut out);
+            });
+        })
+        .bench_function("apply changes of 20k tasks with one changing", |b| {
+            let root = Tree::new();
+            let mut tasks: Vec<_> = (0..20_000).map(|_| root.add_child("task")).collect();
+            tasks[0].init(None, None);
+            let counter = tasks[0].counter();
+            let (mut changes, mut out) = (Changes::default(), Vec::new());
+            root.changes_since(None, &mut changes);
+            changes.apply_to(&mut out);
+            b.iter(|| {
+                counter.inc();
+                root.changes_since(Some(changes.generation), &mut changes);
+                changes.apply_to(&mut out);
+            });
+        });
 }
 
 criterion_group!(benches, usage);
@@ -25,8 +25,11 @@ async fn work_forever(mut args: args::Options) -> Result {
     {
         let mut sp = progress.add_child("preparation");
         sp.info("warming up");
+        sp.debug("checking the engine");
         spawn(async move {
-            async_io::Timer::after(Duration::from_millis(500)).await;
+            async_io::Timer::after(Duration::from_millis(250)).await;
+            sp.warn("engine is running hot");
+            async_io::Timer::after(Duration::from_millis(250)).await;
             sp.fail("engine failure");
             async_io::Timer::after(Duration::from_millis(750)).await;
             sp.done("warmup complete");
@@ -53,7 +56,7 @@ async fn work_forever(mut args: args::Options) -> Result {
 
     loop {
         let local_work = new_chunk_of_work(
-            NestingLevel(thread_rng().gen_range(0..=Key::max_level())),
+            NestingLevel(thread_rng().gen_range(0..=MAX_NESTING_LEVEL)),
             progress.clone(),
             speed,
             changing_names,
@@ -66,7 +69,7 @@ async fn work_forever(mut args: args::Options) -> Result {
         };
         let pooled_work = (0..num_chunks).map(|_| {
             spawn(new_chunk_of_work(
-                NestingLevel(thread_rng().gen_range(0..=Key::max_level())),
+                NestingLevel(thread_rng().gen_range(0..=MAX_NESTING_LEVEL)),
                 progress.clone(),
                 speed,
                 changing_names,
@@ -109,19 +112,27 @@ async fn work_item(mut progress: Item, speed: f32, changing_names: bool) {
             UNITS.choose(&mut thread_rng()).copied().map(Into::into)
         },
     );
+    let lines = thread_rng()
+        .gen_bool(0.2)
+        .then(|| progress.add_counter("lines", None, Some("lines".into())));
 
     for step in 0..max {
+        progress.wait_if_paused_async().await;
         progress.set(step as Step);
+        if let Some(lines) = lines.as_ref() {
+            lines.inc_by(thread_rng().gen_range(10..=100));
+        }
         let delay_ms = if thread_rng().gen_bool(CHANCE_TO_BLOCK_PER_STEP) {
             let eta = if thread_rng().gen_bool(CHANCE_TO_SHOW_ETA) {
                 Some(SystemTime::now().add(Duration::from_millis(LONG_WORK_DELAY_MS)))
             } else {
                 None
             };
+            let reason = format!("{} at step {}", REASONS.choose(&mut thread_rng()).unwrap(), step);
             if thread_rng().gen_bool(0.5) {
-                progress.halted(REASONS.choose(&mut thread_rng()).unwrap(), eta);
+                progress.halted(reason, eta);
             } else {
-                progress.blocked(REASONS.choose(&mut thread_rng()).unwrap(), eta);
+                progress.blocked(reason, eta);
             }
             thread_rng().gen_range(WORK_DELAY_MS..=LONG_WORK_DELAY_MS)
         } else {
@@ -186,11 +197,7 @@ struct NestingLevel(u8);
 type Result = std::result::Result<(), Box<dyn Error + Send>>;
 
 use futures_util::{future::join_all, future::Either, FutureExt};
-use prodash::{
-    progress::{Key, Step},
-    tree::Item,
-    Tree,
-};
+use prodash::{progress::Step, tree::Item, Tree};
 use rand::prelude::*;
 use std::{
     error::Error,
@@ -245,6 +252,7 @@ const LONG_WORK_DELAY_MS: u64 = 2000;
 const SPAWN_DELAY_MS: u64 = 200;
 const CHANCE_TO_BLOCK_PER_STEP: f64 = 1.0 / 100.0;
 const CHANCE_TO_SHOW_ETA: f64 = 0.5;
+const MAX_NESTING_LEVEL: u8 = 6;
 
 mod shared;
 use shared::args;
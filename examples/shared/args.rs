@@ -63,6 +63,10 @@ pub struct Options {
     #[argh(option)]
     pub line_end: Option<prodash::progress::key::Level>,
 
+    /// if set (default: false), the time elapsed since each task started will be shown along with an estimate of when it's done.
+    #[argh(switch, short = 'e')]
+    pub elapsed_and_eta: bool,
+
     /// if set (default: false), we will stop running the TUI once there the list of drawable progress items is empty.
     #[argh(switch)]
     pub stop_if_empty_progress: bool,
@@ -44,6 +44,7 @@ pub fn launch_ambient_gui(
                     frames_per_second: args.fps,
                     keep_running_if_progress_is_empty: true,
                     throughput,
+                    elapsed_and_eta: args.elapsed_and_eta,
                     ..Default::default()
                 }
                 .auto_configure(line::StreamKind::Stderr),
@@ -65,6 +66,7 @@ pub fn launch_ambient_gui(
                         frames_per_second: args.fps,
                         recompute_column_width_every_nth_frame: args.recompute_column_width_every_nth_frame,
                         throughput,
+                        elapsed_and_eta: args.elapsed_and_eta,
                         ..tui::Options::default()
                     },
                     futures_util::stream::select(
@@ -1,4 +1,5 @@
 #![deny(unsafe_code, missing_docs)]
+#![allow(clippy::empty_docs)]
 
 /*!
 Prodash is a dashboard for displaying the progress of concurrent application.
@@ -25,6 +26,8 @@ If the feature `progress-tree-log` is enabled (default), most calls to `progress
 That way, even without a terminal user interface, there will be progress messages.
 Please note that logging to stdout should not be performed with this feature enabled and a terminal user interface is shown, as this will
 seriously interfere with the TUI.
+Instead, install a [`tree::Logger`](./tree/struct.Logger.html) to turn log records into messages of the progress tree, which
+are shown in the message pane of the TUI and above the progress of the line renderer.
 
 # A demo application
 
@@ -47,6 +50,12 @@ pub use tree::{Options as TreeOptions, Root as Tree};
 ///
 pub mod render;
 
+#[cfg(feature = "remote")]
+///
+pub mod remote;
+
+#[cfg(feature = "progress-tree-log")]
+pub use log::debug;
 #[cfg(feature = "progress-tree-log")]
 pub use log::info;
 #[cfg(feature = "progress-tree-log")]
@@ -86,4 +95,10 @@ mod log {
         (target: $target:expr, $($arg:tt)+) => {};
         ($($arg:tt)+) => {};
     }
+    /// Stub
+    #[macro_export(local_inner_macros)]
+    macro_rules! debug {
+        (target: $target:expr, $($arg:tt)+) => {};
+        ($($arg:tt)+) => {};
+    }
 }
@@ -1,7 +1,9 @@
-use std::time::SystemTime;
+use crate::progress::Key;
+use std::{fmt, time::SystemTime};
 
 /// The severity of a message
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MessageLevel {
     /// Rarely sent information related to the progress, not to be confused with the progress itself
     Info,
@@ -9,12 +11,17 @@ pub enum MessageLevel {
     Failure,
     /// Indicates a task was completed successfully
     Success,
+    /// Used to indicate a recoverable problem which doesn't cause the task to fail
+    Warning,
+    /// Detailed information which is typically only of interest when investigating issues
+    Debug,
 }
 
 /// A message to be stored along with the progress tree.
 ///
 /// It is created by [`Tree::message(…)`](./struct.Item.html#method.message).
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Message {
     /// The time at which the message was sent.
     pub time: SystemTime,
@@ -22,8 +29,156 @@ pub struct Message {
     pub level: MessageLevel,
     /// The name of the task that created the `Message`
     pub origin: String,
+    /// The key of the task that created the `Message`, if known.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub key: Option<Key>,
+    /// The names of all tasks from the top-level down to and including the `origin`, or empty if unknown.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub path: Vec<String>,
     /// The message itself
     pub message: String,
+    /// Typed key/value pairs providing machine-readable details, in the order they were given.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fields: Vec<Field>,
+}
+
+impl Message {
+    /// Display the [`path`](#structfield.path) of the task that created the message with `separator` between the names,
+    /// or just the `origin` if the path is unknown.
+    pub fn display_path<'a>(&'a self, separator: &'a str) -> impl fmt::Display + 'a {
+        struct Path<'a>(&'a Message, &'a str);
+        impl<'a> fmt::Display for Path<'a> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                if self.0.path.is_empty() {
+                    return f.write_str(&self.0.origin);
+                }
+                for (index, name) in self.0.path.iter().enumerate() {
+                    if index != 0 {
+                        f.write_str(self.1)?;
+                    }
+                    f.write_str(name)?;
+                }
+                Ok(())
+            }
+        }
+        Path(self, separator)
+    }
+}
+
+/// A typed key/value pair attached to a [`Message`].
+///
+/// ```rust
+/// use prodash::messages::Field;
+/// let fields = vec![Field::new("path", "src/lib.rs"), Field::new("line", 42u32), Field::new("fatal", false)];
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Field {
+    /// The name of the field
+    pub key: String,
+    /// The value of the field
+    pub value: FieldValue,
+}
+
+impl Field {
+    /// Create a new field from `key` and `value`.
+    pub fn new(key: impl Into<String>, value: impl Into<FieldValue>) -> Self {
+        Field {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.key, self.value)
+    }
+}
+
+/// The value of a [`Field`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum FieldValue {
+    String(String),
+    Int(i64),
+    Uint(u64),
+    Float(Float),
+    Bool(bool),
+}
+
+/// A floating point [`FieldValue`], which is equal to another if their bits are equal so that messages can be `Eq`.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+pub struct Float(pub f64);
+
+impl PartialEq for Float {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for Float {}
+
+impl fmt::Display for Float {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<f64> for Float {
+    fn from(v: f64) -> Self {
+        Float(v)
+    }
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::String(v) => v.fmt(f),
+            FieldValue::Int(v) => v.fmt(f),
+            FieldValue::Uint(v) => v.fmt(f),
+            FieldValue::Float(v) => v.fmt(f),
+            FieldValue::Bool(v) => v.fmt(f),
+        }
+    }
+}
+
+macro_rules! impl_from_for_field_value {
+    ($($variant:ident($target:ty) <= $($source:ty),+);+) => {
+        $($(
+            impl From<$source> for FieldValue {
+                fn from(v: $source) -> Self {
+                    FieldValue::$variant((v as $target).into())
+                }
+            }
+        )+)+
+    };
+}
+
+impl_from_for_field_value!(
+    Int(i64) <= i8, i16, i32, i64, isize;
+    Uint(u64) <= u8, u16, u32, u64, usize;
+    Float(f64) <= f32, f64
+);
+
+impl From<bool> for FieldValue {
+    fn from(v: bool) -> Self {
+        FieldValue::Bool(v)
+    }
+}
+
+impl From<String> for FieldValue {
+    fn from(v: String) -> Self {
+        FieldValue::String(v)
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(v: &str) -> Self {
+        FieldValue::String(v.into())
+    }
 }
 
 /// A ring buffer for messages.
@@ -46,12 +201,31 @@ impl MessageRingBuffer {
 
     /// Push a `message` from `origin` at severity `level` into the buffer, possibly overwriting the last message added.
     pub fn push_overwrite(&mut self, level: MessageLevel, origin: String, message: impl Into<String>) {
-        let msg = Message {
+        self.push_overwrite_with_fields(level, origin, message, Vec::new())
+    }
+
+    /// Push a `message` with `fields` from `origin` at severity `level` into the buffer, possibly overwriting the
+    /// last message added.
+    pub fn push_overwrite_with_fields(
+        &mut self,
+        level: MessageLevel,
+        origin: String,
+        message: impl Into<String>,
+        fields: Vec<Field>,
+    ) {
+        self.push_overwrite_message(Message {
             time: SystemTime::now(),
             level,
             origin,
+            key: None,
+            path: Vec::new(),
             message: message.into(),
-        };
+            fields,
+        })
+    }
+
+    /// Push `msg` into the buffer, possibly overwriting the last message added.
+    pub fn push_overwrite_message(&mut self, msg: Message) {
         if self.has_capacity() {
             self.buf.push(msg)
         } else {
@@ -75,10 +249,17 @@ impl MessageRingBuffer {
 
     /// Copy all new messages into `out` that where received since the last time this method was called provided
     /// its `previous` return value.
+    ///
+    /// The returned state also knows how many messages were [dropped](MessageCopyState::dropped()) as they were overwritten
+    /// before they could be copied. A `previous` state which wasn't returned by this buffer is treated like `None`.
     pub fn copy_new(&self, out: &mut Vec<Message>, previous: Option<MessageCopyState>) -> MessageCopyState {
         out.clear();
+        let previous = previous.filter(|p| self.is_consistent_with(p));
+        let previous_total = previous.as_ref().map(|p| p.total).unwrap_or(0);
         match previous {
-            Some(MessageCopyState { cursor, buf_len, total }) => {
+            Some(MessageCopyState {
+                cursor, buf_len, total, ..
+            }) => {
                 if self.total.saturating_sub(total) >= self.buf.capacity() {
                     self.copy_all(out);
                 } else {
@@ -86,7 +267,7 @@ impl MessageRingBuffer {
                     let cursor_ofs: isize = self.cursor as isize - cursor as isize;
                     match cursor_ofs {
                         // there was some capacity left without wrapping around
-                        c if c == 0 => {
+                        0 => {
                             out.extend_from_slice(&self.buf[self.buf.len() - new_elements_below_cap..]);
                         }
                         // cursor advanced
@@ -108,19 +289,81 @@ impl MessageRingBuffer {
             cursor: self.cursor,
             buf_len: self.buf.len(),
             total: self.total,
+            dropped: self.total.wrapping_sub(previous_total).saturating_sub(self.buf.len()),
         }
     }
 
     fn has_capacity(&self) -> bool {
         self.buf.len() < self.buf.capacity()
     }
+
+    /// Returns true if `state` could have been returned by us, which keeps it from pointing outside of our buffer.
+    fn is_consistent_with(&self, state: &MessageCopyState) -> bool {
+        state.cursor < self.buf.len().max(1) && state.buf_len <= self.buf.len() && state.total <= self.total
+    }
 }
 
 /// State used to keep track of what's new since the last time message were copied.
 ///
-/// Note that due to the nature of a ring buffer, there is no guarantee that you see all messages.
+/// Note that due to the nature of a ring buffer, there is no guarantee that you see all messages, but
+/// [`dropped()`](MessageCopyState::dropped()) tells how many were missed.
+///
+/// Only the amount of dropped messages is serialized, as the position in the buffer is meaningless elsewhere.
+#[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MessageCopyState {
+    #[cfg_attr(feature = "serde", serde(skip))]
     cursor: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
     buf_len: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
     total: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    dropped: usize,
+}
+
+impl MessageCopyState {
+    /// The amount of messages that were overwritten before the copy that produced this state could pick them up.
+    ///
+    /// If there was no previous state, these are all messages that were ever overwritten in the buffer.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+}
+
+/// Log `args` at `level` for `target`, passing `fields` as structured key/value pairs.
+#[cfg(any(feature = "progress-tree-log", feature = "progress-log"))]
+pub(crate) fn log_with_fields(target: &'static str, level: log::Level, fields: &[Field], args: fmt::Arguments<'_>) {
+    struct Fields<'a>(&'a [Field]);
+
+    impl<'a> log::kv::Source for Fields<'a> {
+        fn visit<'kvs>(&'kvs self, visitor: &mut dyn log::kv::VisitSource<'kvs>) -> Result<(), log::kv::Error> {
+            for field in self.0 {
+                let value = match &field.value {
+                    FieldValue::String(v) => log::kv::Value::from(v.as_str()),
+                    FieldValue::Int(v) => log::kv::Value::from(*v),
+                    FieldValue::Uint(v) => log::kv::Value::from(*v),
+                    FieldValue::Float(v) => log::kv::Value::from(v.0),
+                    FieldValue::Bool(v) => log::kv::Value::from(*v),
+                };
+                visitor.visit_pair(log::kv::Key::from_str(&field.key), value)?;
+            }
+            Ok(())
+        }
+    }
+
+    if level > log::max_level() {
+        return;
+    }
+    let logger = log::logger();
+    let metadata = log::Metadata::builder().level(level).target(target).build();
+    if logger.enabled(&metadata) {
+        logger.log(
+            &log::Record::builder()
+                .metadata(metadata)
+                .args(args)
+                .key_values(&Fields(fields))
+                .build(),
+        );
+    }
 }
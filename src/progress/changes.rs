@@ -0,0 +1,54 @@
+use crate::progress::{Key, Task};
+
+/// A monotonically increasing counter identifying the state of a progress tree at some point in time.
+pub type Generation = u64;
+
+/// Tasks that were added, changed or removed since a previous [`Generation`], as obtained by
+/// [`Root::changes_since(…)`][crate::Root::changes_since()].
+#[derive(Clone, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Changes {
+    /// The generation to pass to the next call to `changes_since(…)` to receive only what changed after this call.
+    pub generation: Generation,
+    /// If true, `updated` contains all tasks in the tree instead of only the changed ones, and previously
+    /// obtained tasks should be discarded.
+    pub is_complete: bool,
+    /// Tasks which were added or changed, sorted by key.
+    ///
+    /// **Note** that it may contain tasks which didn't actually change.
+    pub updated: Vec<(Key, Task)>,
+    /// The keys of tasks that were removed, sorted.
+    pub removed: Vec<Key>,
+}
+
+impl Changes {
+    /// Apply all changes to the `sorted` tasks of a previous snapshot, leaving `updated` and `removed` empty.
+    ///
+    /// Afterwards `sorted` is equivalent to the output of [`Root::sorted_snapshot(…)`][crate::Root::sorted_snapshot()].
+    pub fn apply_to(&mut self, sorted: &mut Vec<(Key, Task)>) {
+        if self.is_complete {
+            sorted.clear();
+            sorted.append(&mut self.updated);
+            self.removed.clear();
+            return;
+        }
+        if !self.removed.is_empty() {
+            let removed = &self.removed;
+            sorted.retain(|(key, _)| removed.binary_search(key).is_err());
+            self.removed.clear();
+        }
+        let mut needs_sorting = false;
+        for (key, task) in self.updated.drain(..) {
+            match sorted.binary_search_by(|(other, _)| other.cmp(&key)) {
+                Ok(index) => sorted[index].1 = task,
+                Err(_) => {
+                    sorted.push((key, task));
+                    needs_sorting = true;
+                }
+            }
+        }
+        if needs_sorting {
+            sorted.sort_by(|a, b| a.0.cmp(&b.0));
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use crate::{progress::Step, unit, Progress, Unit};
+use std::io::{self, BufRead, Read, Seek, SeekFrom, Write};
+
+/// The unit in which the adapters of this module report progress.
+fn bytes() -> Unit {
+    #[cfg(feature = "unit-bytes")]
+    {
+        unit::dynamic_and_mode(unit::Bytes, unit::display::Mode::with_throughput())
+    }
+    #[cfg(not(feature = "unit-bytes"))]
+    {
+        unit::label_and_mode("bytes", unit::display::Mode::with_throughput())
+    }
+}
+
+/// Determine the length of the stream of `inner` while keeping its position, returning both.
+fn len_and_position(inner: &mut impl Seek) -> io::Result<(u64, u64)> {
+    let position = inner.stream_position()?;
+    let len = inner.seek(SeekFrom::End(0))?;
+    if len != position {
+        inner.seek(SeekFrom::Start(position))?;
+    }
+    Ok((len, position))
+}
+
+/// Wraps a reader to increment a [`Progress`] by the amount of bytes read or [consumed](BufRead::consume()).
+///
+/// With the `futures-lite` dependency enabled, as with `render-tui`, it also wraps `AsyncRead`, `AsyncBufRead`
+/// and `AsyncSeek` implementations.
+///
+/// Seeking sets the progress to the new position, which is most useful if it was created with
+/// [`from_seekable()`](Reader::from_seekable()).
+///
+/// Wrap the progress in a [`ThroughputOnDrop`](super::ThroughputOnDrop) to emit a summary once the transfer ends
+/// and the reader is dropped.
+/// ```rust
+/// use prodash::progress::{io::Reader, ThroughputOnDrop};
+/// use std::io::Read;
+/// let tree = prodash::Tree::new();
+/// let data = vec![0u8; 1024];
+/// let mut reader = Reader::with_len(
+///     data.as_slice(),
+///     data.len() as u64,
+///     ThroughputOnDrop::new(tree.add_child("reading")),
+/// );
+/// let mut out = Vec::new();
+/// reader.read_to_end(&mut out)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Reader<R, P> {
+    inner: R,
+    progress: P,
+}
+
+impl<R, P> Reader<R, P>
+where
+    P: Progress,
+{
+    /// Wrap `inner` to report the bytes read to `progress`, which is initialized as unbounded.
+    pub fn new(inner: R, mut progress: P) -> Self {
+        progress.init(None, Some(bytes()));
+        Reader { inner, progress }
+    }
+
+    /// Wrap `inner` to report the bytes read to `progress`, which is initialized with `len` bytes as upper bound.
+    pub fn with_len(inner: R, len: u64, mut progress: P) -> Self {
+        progress.init(Some(len as Step), Some(bytes()));
+        Reader { inner, progress }
+    }
+
+    /// Wrap `inner` to report its position to `progress`, which is initialized with the length of the stream
+    /// as upper bound, as determined by seeking to its end and back.
+    pub fn from_seekable(mut inner: R, mut progress: P) -> io::Result<Self>
+    where
+        R: Seek,
+    {
+        let (len, position) = len_and_position(&mut inner)?;
+        progress.init(Some(len as Step), Some(bytes()));
+        progress.set(position as Step);
+        Ok(Reader { inner, progress })
+    }
+}
+
+impl<R, P> Reader<R, P> {
+    /// Return the wrapped reader.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Return the wrapped reader mutably. Bytes read from it directly aren't reported.
+    pub fn inner_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Return the progress the bytes read are reported to.
+    pub fn progress(&self) -> &P {
+        &self.progress
+    }
+
+    /// Return the progress the bytes read are reported to, mutably.
+    pub fn progress_mut(&mut self) -> &mut P {
+        &mut self.progress
+    }
+
+    /// Return the wrapped reader and progress.
+    pub fn into_inner(self) -> (R, P) {
+        (self.inner, self.progress)
+    }
+}
+
+impl<R: Read, P: Progress> Read for Reader<R, P> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes = self.inner.read(buf)?;
+        self.progress.inc_by(bytes as Step);
+        Ok(bytes)
+    }
+}
+
+impl<R: BufRead, P: Progress> BufRead for Reader<R, P> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.progress.inc_by(amt as Step);
+    }
+}
+
+impl<R: Seek, P: Progress> Seek for Reader<R, P> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = self.inner.seek(pos)?;
+        self.progress.set(position as Step);
+        Ok(position)
+    }
+}
+
+/// Wraps a writer to increment a [`Progress`] by the amount of bytes written.
+///
+/// With the `futures-lite` dependency enabled, as with `render-tui`, it also wraps `AsyncWrite` and `AsyncSeek`
+/// implementations.
+///
+/// Seeking sets the progress to the new position.
+#[derive(Debug)]
+pub struct Writer<W, P> {
+    inner: W,
+    progress: P,
+}
+
+impl<W, P> Writer<W, P>
+where
+    P: Progress,
+{
+    /// Wrap `inner` to report the bytes written to `progress`, which is initialized as unbounded.
+    pub fn new(inner: W, mut progress: P) -> Self {
+        progress.init(None, Some(bytes()));
+        Writer { inner, progress }
+    }
+
+    /// Wrap `inner` to report the bytes written to `progress`, which is initialized with the `len` bytes expected
+    /// to be written as upper bound.
+    pub fn with_len(inner: W, len: u64, mut progress: P) -> Self {
+        progress.init(Some(len as Step), Some(bytes()));
+        Writer { inner, progress }
+    }
+}
+
+impl<W, P> Writer<W, P> {
+    /// Return the wrapped writer.
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    /// Return the wrapped writer mutably. Bytes written to it directly aren't reported.
+    pub fn inner_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Return the progress the bytes written are reported to.
+    pub fn progress(&self) -> &P {
+        &self.progress
+    }
+
+    /// Return the progress the bytes written are reported to, mutably.
+    pub fn progress_mut(&mut self) -> &mut P {
+        &mut self.progress
+    }
+
+    /// Return the wrapped writer and progress.
+    pub fn into_inner(self) -> (W, P) {
+        (self.inner, self.progress)
+    }
+}
+
+impl<W: Write, P: Progress> Write for Writer<W, P> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let bytes = self.inner.write(buf)?;
+        self.progress.inc_by(bytes as Step);
+        Ok(bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Seek, P: Progress> Seek for Writer<W, P> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = self.inner.seek(pos)?;
+        self.progress.set(position as Step);
+        Ok(position)
+    }
+}
+
+#[cfg(feature = "futures-lite")]
+mod non_blocking {
+    use super::{Reader, Writer};
+    use crate::{progress::Step, Progress};
+    use futures_lite::io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite};
+    use std::{
+        io::{self, SeekFrom},
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    impl<R, P> AsyncRead for Reader<R, P>
+    where
+        R: AsyncRead + Unpin,
+        P: Progress + Unpin,
+    {
+        fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+            let this = self.get_mut();
+            let res = Pin::new(&mut this.inner).poll_read(cx, buf);
+            if let Poll::Ready(Ok(bytes)) = res {
+                this.progress.inc_by(bytes as Step);
+            }
+            res
+        }
+    }
+
+    impl<R, P> AsyncBufRead for Reader<R, P>
+    where
+        R: AsyncBufRead + Unpin,
+        P: Progress + Unpin,
+    {
+        fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+            Pin::new(&mut self.get_mut().inner).poll_fill_buf(cx)
+        }
+
+        fn consume(self: Pin<&mut Self>, amt: usize) {
+            let this = self.get_mut();
+            Pin::new(&mut this.inner).consume(amt);
+            this.progress.inc_by(amt as Step);
+        }
+    }
+
+    impl<R, P> AsyncSeek for Reader<R, P>
+    where
+        R: AsyncSeek + Unpin,
+        P: Progress + Unpin,
+    {
+        fn poll_seek(self: Pin<&mut Self>, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<io::Result<u64>> {
+            let this = self.get_mut();
+            let res = Pin::new(&mut this.inner).poll_seek(cx, pos);
+            if let Poll::Ready(Ok(position)) = res {
+                this.progress.set(position as Step);
+            }
+            res
+        }
+    }
+
+    impl<W, P> AsyncWrite for Writer<W, P>
+    where
+        W: AsyncWrite + Unpin,
+        P: Progress + Unpin,
+    {
+        fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+            let this = self.get_mut();
+            let res = Pin::new(&mut this.inner).poll_write(cx, buf);
+            if let Poll::Ready(Ok(bytes)) = res {
+                this.progress.inc_by(bytes as Step);
+            }
+            res
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner).poll_close(cx)
+        }
+    }
+
+    impl<W, P> AsyncSeek for Writer<W, P>
+    where
+        W: AsyncSeek + Unpin,
+        P: Progress + Unpin,
+    {
+        fn poll_seek(self: Pin<&mut Self>, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<io::Result<u64>> {
+            let this = self.get_mut();
+            let res = Pin::new(&mut this.inner).poll_seek(cx, pos);
+            if let Poll::Ready(Ok(position)) = res {
+                this.progress.set(position as Step);
+            }
+            res
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{progress::Step, Progress};
+use std::time::Instant;
+
+/// Determines by how much each item advances the progress of an adapter.
+pub trait Count<T> {
+    /// Return the amount of steps `item` makes.
+    fn count(&mut self, item: &T) -> Step;
+}
+
+/// Count each item as one step, as used by `with_progress(…)`.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Items;
+
+impl<T> Count<T> for Items {
+    fn count(&mut self, _item: &T) -> Step {
+        1
+    }
+}
+
+impl<T, F> Count<T> for F
+where
+    F: FnMut(&T) -> Step,
+{
+    fn count(&mut self, item: &T) -> Step {
+        self(item)
+    }
+}
+
+/// Decides how an adapter reports that its items are exhausted.
+#[derive(Debug)]
+struct Finish {
+    start: Instant,
+    done_message: Option<String>,
+    finished: bool,
+}
+
+impl Finish {
+    /// Initialize `progress` with `max` as upper bound, or as unbounded.
+    fn new(progress: &mut impl Progress, max: Option<Step>) -> Self {
+        progress.init(max, progress.unit());
+        Finish {
+            start: Instant::now(),
+            done_message: None,
+            finished: false,
+        }
+    }
+
+    /// Emit the done message or throughput summary once.
+    fn finish(&mut self, progress: &mut impl Progress) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+        match self.done_message.take() {
+            Some(message) => progress.done(message),
+            None => progress.show_throughput(self.start),
+        }
+    }
+}
+
+/// The upper bound of a `size_hint` if it is exact.
+fn exact_len((lower, upper): (usize, Option<usize>)) -> Option<usize> {
+    upper.filter(|upper| *upper == lower)
+}
+
+/// Wrap iterators to drive a [`Progress`] as their items are consumed.
+pub trait IteratorExt: Iterator + Sized {
+    /// Increment `progress` with each item returned by this iterator, and show the throughput once it is exhausted.
+    ///
+    /// `progress` is initialized with the amount of items as upper bound if [`size_hint()`](Iterator::size_hint())
+    /// knows it exactly, or as unbounded otherwise.
+    /// ```rust
+    /// use prodash::progress::iter::IteratorExt;
+    /// let tree = prodash::Tree::new();
+    /// let sum: usize = (1..=10).with_progress(tree.add_child("summing")).sum();
+    /// # assert_eq!(sum, 55);
+    /// ```
+    fn with_progress<P: Progress>(self, mut progress: P) -> Iter<Self, P> {
+        let finish = Finish::new(&mut progress, exact_len(self.size_hint()));
+        Iter {
+            inner: self,
+            progress,
+            count: Items,
+            finish,
+        }
+    }
+
+    /// Like [`with_progress()`](IteratorExt::with_progress()), but increment `progress` by the amount returned by
+    /// `count` for each item, like the length of chunks of bytes. `progress` is initialized as unbounded.
+    fn with_progress_by<P, C>(self, mut progress: P, count: C) -> Iter<Self, P, C>
+    where
+        P: Progress,
+        C: Count<Self::Item>,
+    {
+        let finish = Finish::new(&mut progress, None);
+        Iter {
+            inner: self,
+            progress,
+            count,
+            finish,
+        }
+    }
+}
+
+impl<I: Iterator> IteratorExt for I {}
+
+/// An iterator incrementing a [`Progress`] with each item, as created by [`IteratorExt::with_progress()`].
+#[derive(Debug)]
+pub struct Iter<I, P, C = Items> {
+    inner: I,
+    progress: P,
+    count: C,
+    finish: Finish,
+}
+
+impl<I, P, C> Iter<I, P, C> {
+    /// Emit `message` as [done message](Progress::done()) once the iterator is exhausted, instead of the throughput.
+    pub fn done_message(mut self, message: impl Into<String>) -> Self {
+        self.finish.done_message = Some(message.into());
+        self
+    }
+
+    /// Return the progress the items are reported to.
+    pub fn progress(&self) -> &P {
+        &self.progress
+    }
+
+    /// Return the wrapped iterator and progress.
+    pub fn into_inner(self) -> (I, P) {
+        (self.inner, self.progress)
+    }
+}
+
+impl<I, P, C> Iterator for Iter<I, P, C>
+where
+    I: Iterator,
+    P: Progress,
+    C: Count<I::Item>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.inner.next() {
+            Some(item) => {
+                self.progress.inc_by(self.count.count(&item));
+                Some(item)
+            }
+            None => {
+                self.finish.finish(&mut self.progress);
+                None
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(feature = "futures-core")]
+mod stream {
+    use super::{exact_len, Count, Finish, Items};
+    use crate::Progress;
+    use std::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    /// Wrap streams to drive a [`Progress`] as their items are consumed.
+    pub trait StreamExt: futures_core::Stream + Sized {
+        /// Increment `progress` with each item returned by this stream, and show the throughput once it is exhausted.
+        ///
+        /// `progress` is initialized with the amount of items as upper bound if
+        /// [`size_hint()`](futures_core::Stream::size_hint()) knows it exactly, or as unbounded otherwise.
+        fn with_progress<P: Progress>(self, mut progress: P) -> Stream<Self, P> {
+            let finish = Finish::new(&mut progress, exact_len(self.size_hint()));
+            Stream {
+                inner: self,
+                progress,
+                count: Items,
+                finish,
+            }
+        }
+
+        /// Like [`with_progress()`](StreamExt::with_progress()), but increment `progress` by the amount returned by
+        /// `count` for each item, like the length of chunks of bytes received. `progress` is initialized as unbounded.
+        fn with_progress_by<P, C>(self, mut progress: P, count: C) -> Stream<Self, P, C>
+        where
+            P: Progress,
+            C: Count<Self::Item>,
+        {
+            let finish = Finish::new(&mut progress, None);
+            Stream {
+                inner: self,
+                progress,
+                count,
+                finish,
+            }
+        }
+    }
+
+    impl<S: futures_core::Stream> StreamExt for S {}
+
+    /// A stream incrementing a [`Progress`] with each item, as created by [`StreamExt::with_progress()`].
+    #[derive(Debug)]
+    pub struct Stream<S, P, C = Items> {
+        inner: S,
+        progress: P,
+        count: C,
+        finish: Finish,
+    }
+
+    impl<S, P, C> Stream<S, P, C> {
+        /// Emit `message` as [done message](Progress::done()) once the stream is exhausted, instead of the throughput.
+        pub fn done_message(mut self, message: impl Into<String>) -> Self {
+            self.finish.done_message = Some(message.into());
+            self
+        }
+
+        /// Return the progress the items are reported to.
+        pub fn progress(&self) -> &P {
+            &self.progress
+        }
+
+        /// Return the wrapped stream and progress.
+        pub fn into_inner(self) -> (S, P) {
+            (self.inner, self.progress)
+        }
+    }
+
+    impl<S, P, C> futures_core::Stream for Stream<S, P, C>
+    where
+        S: futures_core::Stream + Unpin,
+        P: Progress + Unpin,
+        C: Count<S::Item> + Unpin,
+    {
+        type Item = S::Item;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let this = self.get_mut();
+            match Pin::new(&mut this.inner).poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    this.progress.inc_by(this.count.count(&item));
+                    Poll::Ready(Some(item))
+                }
+                Poll::Ready(None) => {
+                    this.finish.finish(&mut this.progress);
+                    Poll::Ready(None)
+                }
+                Poll::Pending => Poll::Pending,
+            }
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            self.inner.size_hint()
+        }
+    }
+}
+#[cfg(feature = "futures-core")]
+pub use stream::{Stream, StreamExt};
@@ -1,25 +1,40 @@
 use crate::progress::Task;
-use std::ops::{Index, IndexMut};
+use std::{
+    ops::{Index, IndexMut},
+    sync::Arc,
+};
 
 /// a level in the hierarchy of key components
-///
-/// _NOTE:_ This means we will show weird behaviour if there are more than 2^16 tasks at the same time on a level
-/// as multiple progress handles will manipulate the same state.
-pub type Level = u8;
+pub type Level = usize;
 
-pub(crate) type Id = u16;
+/// The identifier of an item within its level of hierarchy.
+///
+/// Identifiers are handed out in order and wrap around eventually, but never to one that is still used by a task
+/// on the same level. See [`tree::Item::add_child(…)`][crate::tree::Item::add_child()].
+pub type Id = u32;
 
 /// A type identifying a spot in the hierarchy of `Tree` items.
-#[derive(Copy, Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
-pub struct Key(Option<Id>, Option<Id>, Option<Id>, Option<Id>);
+///
+/// It's a path of [`Id`]s, one per level, which can be nested arbitrarily deep. Cloning it is as cheap as
+/// cloning an `Arc`, and keys sort such that parents come before their children.
+#[derive(Clone, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Key(Arc<[Id]>);
+
+impl Default for Key {
+    fn default() -> Self {
+        Key(Arc::from(&[][..]))
+    }
+}
 
 /// Determines if a sibling is above or below in the given level of hierarchy
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[derive(Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Debug)]
 #[allow(missing_docs)]
 pub enum SiblingLocation {
     Above,
     Below,
     AboveAndBelow,
+    #[default]
     NotFound,
 }
 
@@ -39,52 +54,28 @@ impl SiblingLocation {
     }
 }
 
-impl Default for SiblingLocation {
-    fn default() -> Self {
-        SiblingLocation::NotFound
-    }
-}
-
 /// A type providing information about what's above and below `Tree` items.
-#[derive(Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Debug)]
-pub struct Adjacency(
-    pub SiblingLocation,
-    pub SiblingLocation,
-    pub SiblingLocation,
-    pub SiblingLocation,
-);
+///
+/// It holds one [`SiblingLocation`] per level of hierarchy, starting at level 1.
+#[derive(Clone, Default, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct Adjacency(pub Vec<SiblingLocation>);
 
 impl Adjacency {
     /// Return the level at which this sibling is located in the hierarchy.
     pub fn level(&self) -> Level {
-        use SiblingLocation::*;
-        match self {
-            Adjacency(NotFound, NotFound, NotFound, NotFound) => 0,
-            Adjacency(_a, NotFound, NotFound, NotFound) => 1,
-            Adjacency(_a, _b, NotFound, NotFound) => 2,
-            Adjacency(_a, _b, _c, NotFound) => 3,
-            Adjacency(_a, _b, _c, _d) => 4,
-        }
+        self.0
+            .iter()
+            .rposition(|location| *location != SiblingLocation::NotFound)
+            .map(|idx| idx + 1)
+            .unwrap_or(0)
     }
     /// Get a reference to the sibling location at `level`.
     pub fn get(&self, level: Level) -> Option<&SiblingLocation> {
-        Some(match level {
-            1 => &self.0,
-            2 => &self.1,
-            3 => &self.2,
-            4 => &self.3,
-            _ => return None,
-        })
+        level.checked_sub(1).and_then(|idx| self.0.get(idx))
     }
     /// Get a mutable reference to the sibling location at `level`.
     pub fn get_mut(&mut self, level: Level) -> Option<&mut SiblingLocation> {
-        Some(match level {
-            1 => &mut self.0,
-            2 => &mut self.1,
-            3 => &mut self.2,
-            4 => &mut self.3,
-            _ => return None,
-        })
+        level.checked_sub(1).and_then(move |idx| self.0.get_mut(idx))
     }
 }
 
@@ -103,40 +94,31 @@ impl IndexMut<Level> for Adjacency {
 
 impl Key {
     /// Return the key to the child identified by `child_id` located in a new nesting level below `self`.
-    pub fn add_child(self, child_id: Id) -> Key {
-        match self {
-            Key(None, None, None, None) => Key(Some(child_id), None, None, None),
-            Key(a, None, None, None) => Key(a, Some(child_id), None, None),
-            Key(a, b, None, None) => Key(a, b, Some(child_id), None),
-            Key(a, b, c, None) => Key(a, b, c, Some(child_id)),
-            Key(a, b, c, _d) => {
-                crate::warn!("Maximum nesting level reached. Adding tasks to current parent");
-                Key(a, b, c, Some(child_id))
-            }
-        }
+    pub fn add_child(&self, child_id: Id) -> Key {
+        let mut path = Vec::with_capacity(self.0.len() + 1);
+        path.extend_from_slice(&self.0);
+        path.push(child_id);
+        Key(path.into())
+    }
+
+    /// Return the key of our parent, or `None` if we are at the root level.
+    pub fn parent(&self) -> Option<Key> {
+        self.0.split_last().map(|(_, parent)| Key(parent.into()))
+    }
+
+    /// Return true if `other` is a descendant of ours, or `self` itself.
+    pub fn contains(&self, other: &Key) -> bool {
+        other.0.starts_with(&self.0)
     }
 
     /// The level of hierarchy a node is placed in, i.e. the amount of path components
     pub fn level(&self) -> Level {
-        match self {
-            Key(None, None, None, None) => 0,
-            Key(Some(_), None, None, None) => 1,
-            Key(Some(_), Some(_), None, None) => 2,
-            Key(Some(_), Some(_), Some(_), None) => 3,
-            Key(Some(_), Some(_), Some(_), Some(_)) => 4,
-            _ => unreachable!("This is a bug - Keys follow a certain pattern"),
-        }
+        self.0.len()
     }
 
     /// Return the identifier for the item at `level`.
     fn get(&self, level: Level) -> Option<&Id> {
-        match level {
-            1 => self.0.as_ref(),
-            2 => self.1.as_ref(),
-            3 => self.2.as_ref(),
-            4 => self.3.as_ref(),
-            _ => None,
-        }
+        level.checked_sub(1).and_then(|idx| self.0.get(idx))
     }
 
     /// Return true if the item identified by `other` shares the parent at `parent_level`.
@@ -144,16 +126,10 @@ impl Key {
         if parent_level < 1 {
             return true;
         }
-        for level in 1..=parent_level {
-            if let (Some(lhs), Some(rhs)) = (self.get(level), other.get(level)) {
-                if lhs != rhs {
-                    return false;
-                }
-            } else {
-                return false;
-            }
+        match (self.0.get(..parent_level), other.0.get(..parent_level)) {
+            (Some(lhs), Some(rhs)) => lhs == rhs,
+            _ => false,
         }
-        true
     }
 
     /// Compute the adjacency map for the key in `sorted` at the given `index`.
@@ -164,7 +140,7 @@ impl Key {
         use SiblingLocation::*;
         let key = &sorted[index].0;
         let key_level = key.level();
-        let mut adjecency = Adjacency::default();
+        let mut adjecency = Adjacency(vec![NotFound; key_level]);
         if key_level == 0 {
             return adjecency;
         }
@@ -231,11 +207,6 @@ impl Key {
         }
         adjecency
     }
-
-    /// The maximum amount of path components we can represent.
-    pub const fn max_level() -> Level {
-        4
-    }
 }
 
 impl Index<Level> for Key {
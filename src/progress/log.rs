@@ -1,4 +1,7 @@
-use crate::{messages::MessageLevel, Progress, Unit};
+use crate::{
+    messages::{self, Field, MessageLevel},
+    Progress, Unit,
+};
 use std::time::Duration;
 
 /// A [`Progress`] implementation which displays progress as it happens without the use of a renderer.
@@ -85,6 +88,14 @@ impl Progress for Log {
         self.max
     }
 
+    fn set_max(&mut self, max: Option<usize>) -> Option<usize> {
+        std::mem::replace(&mut self.max, max)
+    }
+
+    fn inc_max(&mut self, step: usize) {
+        self.max = Some(self.max.unwrap_or(0).saturating_add(step));
+    }
+
     fn step(&self) -> usize {
         self.step
     }
@@ -108,11 +119,23 @@ impl Progress for Log {
     }
 
     fn message(&mut self, level: MessageLevel, message: impl Into<String>) {
+        self.message_with_fields(level, message, Vec::new())
+    }
+
+    fn message_with_fields(&mut self, level: MessageLevel, message: impl Into<String>, fields: Vec<Field>) {
         let message: String = message.into();
-        match level {
-            MessageLevel::Info => log::info!("ℹ{} → {}", self.name, message),
-            MessageLevel::Failure => log::error!("𐄂{} → {}", self.name, message),
-            MessageLevel::Success => log::info!("✓{} → {}", self.name, message),
-        }
+        let (level, symbol) = match level {
+            MessageLevel::Info => (log::Level::Info, "ℹ"),
+            MessageLevel::Failure => (log::Level::Error, "𐄂"),
+            MessageLevel::Success => (log::Level::Info, "✓"),
+            MessageLevel::Warning => (log::Level::Warn, "⚠"),
+            MessageLevel::Debug => (log::Level::Debug, "…"),
+        };
+        messages::log_with_fields(
+            module_path!(),
+            level,
+            &fields,
+            format_args!("{}{} → {}", symbol, self.name, message),
+        );
     }
 }
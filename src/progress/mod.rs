@@ -1,5 +1,9 @@
-use crate::unit::Unit;
-use std::time::SystemTime;
+use crate::unit::{self, Unit};
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 ///
 pub mod key;
@@ -8,6 +12,20 @@ pub use key::Key;
 
 mod utils;
 
+/// Adapters for [`std::io`] types which report the bytes they transfer to a [`Progress`](crate::Progress).
+pub mod io;
+
+/// Adapters for iterators and streams which drive a [`Progress`](crate::Progress) as their items are consumed.
+pub mod iter;
+
+mod roll_up;
+pub use roll_up::{roll_up, RollUp};
+#[cfg(feature = "serde")]
+pub(crate) use roll_up::{Percentage, SubtasksDone};
+
+mod changes;
+pub use changes::{Changes, Generation};
+
 #[cfg(feature = "progress-log")]
 mod log;
 #[cfg(feature = "progress-log")]
@@ -19,26 +37,43 @@ pub use utils::{Discard, DoOrDiscard, Either, ThroughputOnDrop};
 pub type Step = usize;
 
 /// Indicate whether a progress can or cannot be made.
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+///
+/// Reasons are shared strings which are cheap to clone along with snapshots, and can be created from both
+/// `&str` and `String`, like `State::Blocked("waiting for lock".into(), None)`.
+#[derive(Clone, Default, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum State {
     /// Indicates a task is blocked and cannot indicate progress, optionally until the
     /// given time. The task cannot easily be interrupted.
-    Blocked(&'static str, Option<SystemTime>),
+    Blocked(Arc<str>, Option<SystemTime>),
     /// Indicates a task cannot indicate progress, optionally until the
     /// given time. The task can be interrupted.
-    Halted(&'static str, Option<SystemTime>),
+    Halted(Arc<str>, Option<SystemTime>),
+    /// Indicates a task was asked to pause and doesn't make progress until it is resumed.
+    /// The task can be interrupted.
+    Paused,
     /// The task is running
+    #[default]
     Running,
+    /// The task has finished with the given outcome and won't make any more progress.
+    Finished(Outcome),
 }
 
-impl Default for State {
-    fn default() -> Self {
-        State::Running
-    }
+/// The final outcome of a task.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Outcome {
+    /// The task completed successfully.
+    Succeeded,
+    /// The task failed.
+    Failed,
+    /// The task was stopped before it could complete.
+    Cancelled,
 }
 
 /// Progress associated with some item in the progress tree.
 #[derive(Clone, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Value {
     /// The amount of progress currently made
     pub step: Step,
@@ -50,6 +85,10 @@ pub struct Value {
     pub unit: Option<Unit>,
     /// Whether progress can be made or not
     pub state: State,
+    /// The time at which the task was created, if known.
+    pub created_at: Option<SystemTime>,
+    /// The time at which the task was initialized to receive progress, if known.
+    pub initialized_at: Option<SystemTime>,
 }
 
 impl Value {
@@ -59,13 +98,97 @@ impl Value {
     pub fn fraction(&self) -> Option<f32> {
         self.done_at.map(|done_at| self.step as f32 / done_at as f32)
     }
+
+    /// Returns the time passed since the task was initialized, or since it was created if the former is unknown.
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.initialized_at
+            .or(self.created_at)
+            .and_then(|start| SystemTime::now().duration_since(start).ok())
+    }
+
+    /// Returns the estimated time until the task is done, or `None` if the progress is unbounded, finished,
+    /// if there isn't enough information yet or if the estimate is too large to be represented.
+    ///
+    /// The estimate is based on the given `throughput` if it's available and non-zero, or on the average
+    /// rate of progress since the task was initialized otherwise.
+    pub fn eta(&self, throughput: Option<&unit::display::Throughput>) -> Option<Duration> {
+        if let State::Finished(_) = self.state {
+            return None;
+        }
+        let remaining = self.done_at?.saturating_sub(self.step);
+        let steps_per_second = throughput
+            .filter(|tp| tp.value_change_in_timespan > 0 && tp.timespan > Duration::default())
+            .map(|tp| tp.value_change_in_timespan as f64 / tp.timespan.as_secs_f64())
+            .or_else(|| {
+                let elapsed = self
+                    .initialized_at
+                    .and_then(|t| SystemTime::now().duration_since(t).ok())?;
+                (self.step > 0 && elapsed > Duration::default()).then(|| self.step as f64 / elapsed.as_secs_f64())
+            })?;
+        Duration::try_from_secs_f64(remaining as f64 / steps_per_second).ok()
+    }
 }
 
 /// The value associated with a spot in the hierarchy.
 #[derive(Clone, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Task {
     /// The name of the `Item` or task.
     pub name: String,
     /// The progress itself, unless this value belongs to an `Item` serving as organizational unit.
     pub progress: Option<Value>,
+    /// If set, the progress of this organizational unit is computed from its children, see [`roll_up()`].
+    pub roll_up: Option<RollUp>,
+    /// The weight of this task when its progress is combined with the one of its siblings, or `1.0` if unset.
+    ///
+    /// Only used if the parent uses [`RollUp::Fraction`].
+    pub weight: Option<f32>,
+    /// Additional quantities tracked next to the primary `progress`, in the order they were added.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub counters: Vec<Counter>,
+    /// If true, the task was asked to cancel, see [`Root::request_cancel()`][crate::Root::request_cancel()].
+    ///
+    /// Renderers show such tasks as cancelling until they are finished.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cancel_requested: bool,
+}
+
+/// An additional named quantity tracked by a task next to its primary [`Value`], like the bytes
+/// received while the primary value counts objects.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Counter {
+    /// The name of the counter, unique within its task.
+    pub name: String,
+    /// The amount of progress currently made
+    pub step: Step,
+    /// The step at which no further progress has to be made, if known.
+    pub done_at: Option<Step>,
+    /// The unit associated with the counter.
+    pub unit: Option<Unit>,
+}
+
+impl Counter {
+    /// Returns a number between `Some(0.0)` and `Some(1.0)`, or `None` if the counter is unbounded.
+    pub fn fraction(&self) -> Option<f32> {
+        self.done_at.map(|done_at| self.step as f32 / done_at as f32)
+    }
+
+    /// Display the counter with its unit along with the given `throughput`, or as `step/done_at name` if it has no unit.
+    pub fn display(&self, throughput: Option<unit::display::Throughput>) -> impl fmt::Display + '_ {
+        struct CounterDisplay<'a>(&'a Counter, Option<unit::display::Throughput>);
+        impl<'a> fmt::Display for CounterDisplay<'a> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let counter = self.0;
+                match counter.unit.as_ref() {
+                    Some(unit) => unit.display(counter.step, counter.done_at, self.1).fmt(f),
+                    None => match counter.done_at {
+                        Some(done_at) => write!(f, "{}/{} {}", counter.step, done_at, counter.name),
+                        None => write!(f, "{} {}", counter.step, counter.name),
+                    },
+                }
+            }
+        }
+        CounterDisplay(self, throughput)
+    }
 }
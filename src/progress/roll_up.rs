@@ -0,0 +1,126 @@
+use crate::{
+    progress::{Key, State, Step, Task, Value},
+    unit::{self, DisplayValue},
+};
+use std::fmt;
+
+/// Determines how the progress of its children is combined into the progress of an organizational task.
+///
+/// Set it with [`tree::Item::set_roll_up(…)`][crate::tree::Item::set_roll_up()], and see [`roll_up()`] for how
+/// it is applied.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RollUp {
+    /// Show how many of the children are done, as in `3 of 8 subtasks done`.
+    ///
+    /// A child is done if it is [finished](State::Finished) or has reached its upper bound. Note that children
+    /// are only counted while they are in the tree, see [`tree::Options::keep_finished`][crate::tree::Options::keep_finished].
+    Count,
+    /// Show the average fraction of all children as combined progress, with each child contributing
+    /// according to its [`weight`](Task::weight).
+    ///
+    /// Unbounded children only contribute once they are finished.
+    Fraction,
+}
+
+/// The resolution of the combined fraction computed with [`RollUp::Fraction`].
+const FRACTION_RESOLUTION: Step = 1000;
+
+/// Compute the progress of all tasks in `sorted` that opted into a [`RollUp`] based on the progress of their
+/// direct children, replacing any previous progress.
+///
+/// Children are processed before their parents, so rolled-up tasks can be nested.
+/// It's vital that the invariant of `sorted` to actually be sorted by key is upheld for the result to be reliable,
+/// as is the case for the output of [`Root::sorted_snapshot(…)`][crate::Root::sorted_snapshot()].
+pub fn roll_up(sorted: &mut [(Key, Task)]) {
+    for index in (0..sorted.len()).rev() {
+        let (parent, rest) = sorted[index..].split_first_mut().expect("index in bounds");
+        let (key, task) = parent;
+        let roll_up = match task.roll_up {
+            Some(roll_up) => roll_up,
+            None => continue,
+        };
+        let level = key.level();
+        let children = rest
+            .iter()
+            .take_while(|(other, _)| other.level() > level && other.shares_parent_with(key, level))
+            .filter(|(other, _)| other.level() == level + 1)
+            .filter_map(|(_, child)| child.progress.as_ref().map(|p| (p, child.weight.unwrap_or(1.0))));
+        task.progress = Some(match roll_up {
+            RollUp::Count => {
+                let (done, total) = children.fold((0, 0), |(done, total), (p, _weight)| {
+                    (done + is_done(p) as Step, total + 1)
+                });
+                Value {
+                    step: done,
+                    done_at: Some(total),
+                    unit: Some(unit::dynamic(SubtasksDone)),
+                    ..Default::default()
+                }
+            }
+            RollUp::Fraction => {
+                let (weighted_fractions, weights) =
+                    children.fold((0.0, 0.0), |(weighted_fractions, weights), (p, weight)| {
+                        let fraction = if is_done(p) {
+                            1.0
+                        } else {
+                            p.fraction().unwrap_or(0.0).min(1.0)
+                        };
+                        (weighted_fractions + fraction * weight, weights + weight)
+                    });
+                let fraction = if weights > 0.0 {
+                    weighted_fractions / weights
+                } else {
+                    0.0
+                };
+                Value {
+                    step: (fraction * FRACTION_RESOLUTION as f32).round() as Step,
+                    done_at: Some(FRACTION_RESOLUTION),
+                    unit: Some(unit::dynamic(Percentage)),
+                    ..Default::default()
+                }
+            }
+        });
+    }
+}
+
+fn is_done(p: &Value) -> bool {
+    matches!(p.state, State::Finished(_)) || p.done_at.map(|done_at| p.step >= done_at).unwrap_or(false)
+}
+
+pub(crate) struct SubtasksDone;
+
+impl DisplayValue for SubtasksDone {
+    fn separator(&self, w: &mut dyn fmt::Write, _value: Step, _upper: Option<Step>) -> fmt::Result {
+        w.write_str(" of ")
+    }
+    fn display_unit(&self, w: &mut dyn fmt::Write, _value: Step) -> fmt::Result {
+        w.write_str("subtasks done")
+    }
+    #[cfg(feature = "serde")]
+    fn builtin(&self) -> Option<crate::unit::Builtin> {
+        Some(crate::unit::Builtin::SubtasksDone)
+    }
+}
+
+pub(crate) struct Percentage;
+
+impl DisplayValue for Percentage {
+    fn display_current_value(&self, w: &mut dyn fmt::Write, value: Step, upper: Option<Step>) -> fmt::Result {
+        let percentage = upper.map(|upper| value * 100 / upper.max(1)).unwrap_or(0);
+        w.write_fmt(format_args!("{}%", percentage))
+    }
+    fn separator(&self, _w: &mut dyn fmt::Write, _value: Step, _upper: Option<Step>) -> fmt::Result {
+        Ok(())
+    }
+    fn display_upper_bound(&self, _w: &mut dyn fmt::Write, _upper_bound: Step, _value: Step) -> fmt::Result {
+        Ok(())
+    }
+    fn display_unit(&self, _w: &mut dyn fmt::Write, _value: Step) -> fmt::Result {
+        Ok(())
+    }
+    #[cfg(feature = "serde")]
+    fn builtin(&self) -> Option<crate::unit::Builtin> {
+        Some(crate::unit::Builtin::Percentage)
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{messages::MessageLevel, Progress, Unit};
+use crate::{
+    messages::{Field, MessageLevel},
+    Progress, Unit,
+};
 
 /// An implementation of [`Progress`] which discards all calls.
 pub struct Discard;
@@ -14,6 +17,12 @@ impl Progress for Discard {
 
     fn set(&mut self, _step: usize) {}
 
+    fn set_max(&mut self, _max: Option<usize>) -> Option<usize> {
+        None
+    }
+
+    fn inc_max(&mut self, _step: usize) {}
+
     fn step(&self) -> usize {
         0
     }
@@ -82,6 +91,41 @@ where
         }
     }
 
+    fn set_max(&mut self, max: Option<usize>) -> Option<usize> {
+        match self {
+            Either::Left(l) => l.set_max(max),
+            Either::Right(r) => r.set_max(max),
+        }
+    }
+
+    fn inc_max(&mut self, step: usize) {
+        match self {
+            Either::Left(l) => l.inc_max(step),
+            Either::Right(r) => r.inc_max(step),
+        }
+    }
+
+    fn is_cancel_requested(&self) -> bool {
+        match self {
+            Either::Left(l) => l.is_cancel_requested(),
+            Either::Right(r) => r.is_cancel_requested(),
+        }
+    }
+
+    fn is_paused(&self) -> bool {
+        match self {
+            Either::Left(l) => l.is_paused(),
+            Either::Right(r) => r.is_paused(),
+        }
+    }
+
+    fn wait_if_paused(&self) {
+        match self {
+            Either::Left(l) => l.wait_if_paused(),
+            Either::Right(r) => r.wait_if_paused(),
+        }
+    }
+
     fn step(&self) -> usize {
         match self {
             Either::Left(l) => l.step(),
@@ -116,6 +160,13 @@ where
             Either::Right(r) => r.message(level, message),
         }
     }
+
+    fn message_with_fields(&mut self, level: MessageLevel, message: impl Into<String>, fields: Vec<Field>) {
+        match self {
+            Either::Left(l) => l.message_with_fields(level, message, fields),
+            Either::Right(r) => r.message_with_fields(level, message, fields),
+        }
+    }
 }
 
 /// An implementation of `Progress` which can be created easily from `Option<impl Progress>`.
@@ -178,6 +229,26 @@ where
         self.0.max()
     }
 
+    fn set_max(&mut self, max: Option<usize>) -> Option<usize> {
+        self.0.set_max(max)
+    }
+
+    fn inc_max(&mut self, step: usize) {
+        self.0.inc_max(step)
+    }
+
+    fn is_cancel_requested(&self) -> bool {
+        self.0.is_cancel_requested()
+    }
+
+    fn is_paused(&self) -> bool {
+        self.0.is_paused()
+    }
+
+    fn wait_if_paused(&self) {
+        self.0.wait_if_paused()
+    }
+
     fn step(&self) -> usize {
         self.0.step()
     }
@@ -197,6 +268,10 @@ where
     fn message(&mut self, level: MessageLevel, message: impl Into<String>) {
         self.0.message(level, message)
     }
+
+    fn message_with_fields(&mut self, level: MessageLevel, message: impl Into<String>, fields: Vec<Field>) {
+        self.0.message_with_fields(level, message, fields)
+    }
 }
 
 use std::time::Instant;
@@ -234,6 +309,26 @@ impl<T: Progress> Progress for ThroughputOnDrop<T> {
         self.0.max()
     }
 
+    fn set_max(&mut self, max: Option<usize>) -> Option<usize> {
+        self.0.set_max(max)
+    }
+
+    fn inc_max(&mut self, step: usize) {
+        self.0.inc_max(step)
+    }
+
+    fn is_cancel_requested(&self) -> bool {
+        self.0.is_cancel_requested()
+    }
+
+    fn is_paused(&self) -> bool {
+        self.0.is_paused()
+    }
+
+    fn wait_if_paused(&self) {
+        self.0.wait_if_paused()
+    }
+
     fn step(&self) -> usize {
         self.0.step()
     }
@@ -253,6 +348,10 @@ impl<T: Progress> Progress for ThroughputOnDrop<T> {
     fn message(&mut self, level: MessageLevel, message: impl Into<String>) {
         self.0.message(level, message)
     }
+
+    fn message_with_fields(&mut self, level: MessageLevel, message: impl Into<String>, fields: Vec<Field>) {
+        self.0.message_with_fields(level, message, fields)
+    }
 }
 
 impl<T: Progress> Drop for ThroughputOnDrop<T> {
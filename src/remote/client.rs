@@ -0,0 +1,164 @@
+use crate::{
+    messages::{Message, MessageCopyState},
+    progress::{Changes, Generation, Key, Task},
+    remote::protocol::{Connection, Request, Response},
+};
+use std::{
+    io::{self, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// The time after which a server that doesn't answer or accept a request is considered lost.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+trait Stream: Read + Write + Send {}
+impl<T: Read + Write + Send> Stream for T {}
+
+/// Our connection to the server, or `None` if it failed.
+type SharedConnection = Arc<Mutex<Option<Connection<Box<dyn Stream>>>>>;
+
+/// A [`Root`][crate::Root] whose tasks and messages are obtained from a [server](super::serve()),
+/// so that it can be displayed by any renderer.
+///
+/// All calls block until the server answered, for at most 5 seconds when connected through a socket.
+/// If the connection fails, the root appears empty from then on, which can be detected with
+/// [`is_connected()`](#method.is_connected).
+#[derive(Clone)]
+pub struct RemoteRoot {
+    connection: SharedConnection,
+}
+
+impl std::fmt::Debug for RemoteRoot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteRoot")
+            .field("is_connected", &self.is_connected())
+            .finish()
+    }
+}
+
+impl RemoteRoot {
+    /// Connect to a server listening on the TCP socket at `addr`.
+    pub fn connect_tcp(addr: impl ToSocketAddrs) -> io::Result<RemoteRoot> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        Self::from_stream(stream)
+    }
+
+    /// Connect to a server listening on the unix domain socket at `path`.
+    #[cfg(unix)]
+    pub fn connect_unix(path: impl AsRef<std::path::Path>) -> io::Result<RemoteRoot> {
+        let stream = std::os::unix::net::UnixStream::connect(path)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        Self::from_stream(stream)
+    }
+
+    /// Use the given `stream` which is connected to a server.
+    ///
+    /// It should time out if the server stalls, as otherwise calls may block forever.
+    pub fn from_stream(stream: impl Read + Write + Send + 'static) -> io::Result<RemoteRoot> {
+        let stream: Box<dyn Stream> = Box::new(stream);
+        Ok(RemoteRoot {
+            connection: Arc::new(Mutex::new(Some(Connection::handshake(stream, u64::MAX)?))),
+        })
+    }
+
+    /// Returns true if we are still connected to the server.
+    pub fn is_connected(&self) -> bool {
+        self.connection
+            .lock()
+            .expect("no panic while holding the lock")
+            .is_some()
+    }
+
+    /// Send `request` and return the response, or `None` if the connection failed, in which case it is closed.
+    fn call(&self, request: Request) -> Option<Response> {
+        let mut guard = self.connection.lock().expect("no panic while holding the lock");
+        let connection = guard.as_mut()?;
+        let response = connection
+            .send(&request)
+            .and_then(|_| connection.receive::<Response>())
+            .ok()
+            .flatten();
+        if response.is_none() {
+            *guard = None;
+        }
+        response
+    }
+}
+
+impl crate::Root for RemoteRoot {
+    fn messages_capacity(&self) -> usize {
+        match self.call(Request::MessagesCapacity) {
+            Some(Response::Count(capacity)) => capacity,
+            _ => 0,
+        }
+    }
+
+    fn num_tasks(&self) -> usize {
+        match self.call(Request::NumTasks) {
+            Some(Response::Count(num_tasks)) => num_tasks,
+            _ => 0,
+        }
+    }
+
+    fn sorted_snapshot(&self, out: &mut Vec<(Key, Task)>) {
+        *out = match self.call(Request::SortedSnapshot) {
+            Some(Response::Tasks(tasks)) => tasks,
+            _ => Vec::new(),
+        };
+    }
+
+    fn changes_since(&self, generation: Option<Generation>, out: &mut Changes) {
+        *out = match self.call(Request::ChangesSince(generation)) {
+            Some(Response::Changes(changes)) => changes,
+            _ => Changes {
+                is_complete: true,
+                ..Default::default()
+            },
+        };
+    }
+
+    fn copy_messages(&self, out: &mut Vec<Message>) {
+        *out = match self.call(Request::CopyMessages) {
+            Some(Response::Messages(messages)) => messages,
+            _ => Vec::new(),
+        };
+    }
+
+    fn copy_new_messages(&self, out: &mut Vec<Message>, prev: Option<MessageCopyState>) -> MessageCopyState {
+        match self.call(Request::CopyNewMessages(prev.is_some())) {
+            Some(Response::NewMessages(messages, state)) => {
+                *out = messages;
+                state
+            }
+            _ => {
+                out.clear();
+                MessageCopyState::default()
+            }
+        }
+    }
+
+    fn copy_message_history(&self, out: &mut Vec<Message>, max: usize) {
+        *out = match self.call(Request::CopyMessageHistory(max)) {
+            Some(Response::Messages(messages)) => messages,
+            _ => Vec::new(),
+        };
+    }
+
+    fn request_cancel(&self, key: &Key) {
+        self.call(Request::Cancel(key.clone()));
+    }
+
+    fn request_pause(&self, key: &Key) {
+        self.call(Request::Pause(key.clone()));
+    }
+
+    fn request_resume(&self, key: &Key) {
+        self.call(Request::Resume(key.clone()));
+    }
+}
@@ -0,0 +1,32 @@
+//! Serve any [`Root`][crate::Root] over a socket and render it elsewhere.
+//!
+//! A [server](crate::remote::serve()) answers requests for snapshots and messages of a local progress tree, while a [`RemoteRoot`][crate::remote::RemoteRoot]
+//! implements [`Root`][crate::Root] by forwarding all calls to such a server. That way the line and TUI renderers can
+//! display the progress of another process, for instance a headless daemon.
+//!
+//! ```no_run
+//! # fn main() -> std::io::Result<()> {
+//! use prodash::remote;
+//! let tree = prodash::Tree::new();
+//! remote::serve(tree.clone(), std::net::TcpListener::bind("127.0.0.1:3333")?);
+//!
+//! // possibly in another process
+//! let remote_tree = remote::RemoteRoot::connect_tcp("127.0.0.1:3333")?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The protocol consists of newline-delimited JSON values, starting with a handshake in which both sides exchange
+//! their [`PROTOCOL_VERSION`][crate::remote::PROTOCOL_VERSION]. Connections with mismatching versions are closed.
+
+mod protocol;
+pub use protocol::PROTOCOL_VERSION;
+
+mod server;
+pub use server::{serve, Listener};
+
+mod client;
+pub use client::RemoteRoot;
+
+#[cfg(all(test, feature = "progress-tree"))]
+mod tests;
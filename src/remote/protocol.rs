@@ -0,0 +1,109 @@
+use crate::{
+    messages::{Message, MessageCopyState},
+    progress::{Changes, Generation, Key, Task},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+/// The version of the protocol spoken between [`serve()`][super::serve()] and [`RemoteRoot`][super::RemoteRoot].
+///
+/// It changes whenever either side can't understand the other anymore.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// The first value sent by both sides of a connection.
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct Hello {
+    pub version: u32,
+}
+
+/// A request for calling a method of [`Root`][crate::Root].
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) enum Request {
+    MessagesCapacity,
+    NumTasks,
+    SortedSnapshot,
+    ChangesSince(Option<Generation>),
+    CopyMessages,
+    /// Copy messages which are new since the last request of this kind on the same connection, or all of them if `false`.
+    CopyNewMessages(bool),
+    CopyMessageHistory(usize),
+    Cancel(Key),
+    Pause(Key),
+    Resume(Key),
+}
+
+/// The answer to a [`Request`].
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) enum Response {
+    Count(usize),
+    Tasks(Vec<(Key, Task)>),
+    Changes(Changes),
+    Messages(Vec<Message>),
+    NewMessages(Vec<Message>, MessageCopyState),
+    Done,
+}
+
+/// A stream of newline-delimited JSON values.
+pub(crate) struct Connection<S> {
+    stream: BufReader<S>,
+    buf: Vec<u8>,
+    /// The maximum length of lines we receive, including the newline.
+    max_line_len: u64,
+}
+
+impl<S: Read + Write> Connection<S> {
+    /// Establish a connection on `stream` by sending our [`Hello`] and checking that the other side
+    /// speaks the same protocol version, failing to receive any line longer than `max_line_len`.
+    pub(crate) fn handshake(stream: S, max_line_len: u64) -> io::Result<Self> {
+        let mut connection = Connection {
+            stream: BufReader::new(stream),
+            buf: Vec::new(),
+            max_line_len,
+        };
+        connection.send(&Hello {
+            version: PROTOCOL_VERSION,
+        })?;
+        match connection.receive::<Hello>()? {
+            Some(Hello { version }) if version == PROTOCOL_VERSION => Ok(connection),
+            Some(Hello { version }) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "remote speaks protocol version {}, but we need version {}",
+                    version, PROTOCOL_VERSION
+                ),
+            )),
+            None => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "connection closed during handshake",
+            )),
+        }
+    }
+
+    /// Send `value` as a single line.
+    pub(crate) fn send(&mut self, value: &impl Serialize) -> io::Result<()> {
+        self.buf.clear();
+        serde_json::to_writer(&mut self.buf, value)?;
+        self.buf.push(b'\n');
+        let stream = self.stream.get_mut();
+        stream.write_all(&self.buf)?;
+        stream.flush()
+    }
+
+    /// Receive a single value from the next line, or return `None` if the other side closed the connection.
+    pub(crate) fn receive<T: DeserializeOwned>(&mut self) -> io::Result<Option<T>> {
+        self.buf.clear();
+        let len = (&mut self.stream)
+            .take(self.max_line_len)
+            .read_until(b'\n', &mut self.buf)?;
+        if len == 0 {
+            return Ok(None);
+        }
+        if len as u64 == self.max_line_len && self.buf.last() != Some(&b'\n') {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("received a line longer than {} bytes", self.max_line_len),
+            ));
+        }
+        Ok(Some(serde_json::from_slice(&self.buf)?))
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{
+    progress::Changes,
+    remote::protocol::{Connection, Request, Response},
+    Root,
+};
+use std::{
+    io::{self, Read, Write},
+    thread,
+    time::Duration,
+};
+
+/// The time after which connections to clients that don't send a request are closed.
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+/// The time after which connections to clients that don't receive their response are closed.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+/// The maximum length of a request, which is a lot more than needed by any valid request.
+const MAX_REQUEST_LEN: u64 = 64 * 1024;
+/// The maximum amount of messages sent in response to a request for the message history.
+const MAX_MESSAGE_HISTORY: usize = 10_000;
+
+/// A way to accept connections from [`RemoteRoot`][super::RemoteRoot] clients.
+pub trait Listener: Send + 'static {
+    /// The type of stream connecting us to a client.
+    type Stream: Read + Write + Send + 'static;
+
+    /// Block until a client connects and return a stream to communicate with it.
+    ///
+    /// Streams should time out if a client stalls, as the implementations for sockets do after 60 seconds without a
+    /// request or 10 seconds of not receiving a response.
+    fn accept(&self) -> io::Result<Self::Stream>;
+}
+
+impl Listener for std::net::TcpListener {
+    type Stream = std::net::TcpStream;
+
+    fn accept(&self) -> io::Result<Self::Stream> {
+        let (stream, _addr) = std::net::TcpListener::accept(self)?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        Ok(stream)
+    }
+}
+
+#[cfg(unix)]
+impl Listener for std::os::unix::net::UnixListener {
+    type Stream = std::os::unix::net::UnixStream;
+
+    fn accept(&self) -> io::Result<Self::Stream> {
+        let (stream, _addr) = std::os::unix::net::UnixListener::accept(self)?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        Ok(stream)
+    }
+}
+
+/// Serve `root` to all clients connecting to `listener`, each on its own thread.
+///
+/// Failures to accept a connection are logged and retried after a short pause, as they are typically transient,
+/// like running out of file descriptors or a client aborting its connection. The returned thread only stops if `listener`
+/// can't accept connections anymore, indicated by an error of kind [`InvalidInput`][io::ErrorKind::InvalidInput] or
+/// [`Unsupported`][io::ErrorKind::Unsupported], and returns that error.
+///
+/// Connections are closed once the client disconnects, sends an invalid request or a request longer than 64KiB,
+/// or once the stream times out. At most 10000 messages of the message history are sent per request.
+pub fn serve<R>(root: R, listener: impl Listener) -> thread::JoinHandle<io::Result<()>>
+where
+    R: Root + Clone + Send + 'static,
+{
+    const MIN_PAUSE: Duration = Duration::from_millis(5);
+    const MAX_PAUSE: Duration = Duration::from_secs(1);
+    thread::Builder::new()
+        .name("prodash-remote-server".into())
+        .spawn(move || {
+            let mut pause = MIN_PAUSE;
+            loop {
+                let stream = match listener.accept() {
+                    Ok(stream) => stream,
+                    Err(err) if is_fatal(&err) => return Err(err),
+                    Err(err) => {
+                        log_failure("accept a remote connection", &err);
+                        thread::sleep(pause);
+                        pause = (pause * 2).min(MAX_PAUSE);
+                        continue;
+                    }
+                };
+                pause = MIN_PAUSE;
+                let root = root.clone();
+                if let Err(err) = thread::Builder::new()
+                    .name("prodash-remote-connection".into())
+                    .spawn(move || handle_connection(root, stream).ok())
+                {
+                    log_failure("start a thread for a remote connection", &err);
+                }
+            }
+        })
+        .expect("starting a thread works")
+}
+
+#[cfg_attr(not(feature = "progress-tree-log"), allow(unused_variables))]
+fn log_failure(what: &str, err: &io::Error) {
+    crate::warn!("Failed to {}: {}", what, err);
+}
+
+/// Returns true if `err` indicates that a listener can't accept connections anymore.
+fn is_fatal(err: &io::Error) -> bool {
+    matches!(err.kind(), io::ErrorKind::InvalidInput | io::ErrorKind::Unsupported)
+}
+
+fn handle_connection(root: impl Root, stream: impl Read + Write) -> io::Result<()> {
+    let mut connection = Connection::handshake(stream, MAX_REQUEST_LEN)?;
+    let (mut tasks, mut messages, mut changes) = (Vec::new(), Vec::new(), Changes::default());
+    // Kept here as the state refers to the position in our message buffer, which clients can't be trusted with.
+    let mut message_copy_state = None;
+    while let Some(request) = connection.receive::<Request>()? {
+        let response = match request {
+            Request::MessagesCapacity => Response::Count(root.messages_capacity()),
+            Request::NumTasks => Response::Count(root.num_tasks()),
+            Request::SortedSnapshot => {
+                root.sorted_snapshot(&mut tasks);
+                Response::Tasks(std::mem::take(&mut tasks))
+            }
+            Request::ChangesSince(generation) => {
+                root.changes_since(generation, &mut changes);
+                Response::Changes(std::mem::take(&mut changes))
+            }
+            Request::CopyMessages => {
+                root.copy_messages(&mut messages);
+                Response::Messages(std::mem::take(&mut messages))
+            }
+            Request::CopyNewMessages(continued) => {
+                let previous = if continued { message_copy_state.take() } else { None };
+                let state = root.copy_new_messages(&mut messages, previous);
+                message_copy_state = Some(state.clone());
+                Response::NewMessages(std::mem::take(&mut messages), state)
+            }
+            Request::CopyMessageHistory(max) => {
+                root.copy_message_history(&mut messages, max.min(MAX_MESSAGE_HISTORY));
+                Response::Messages(std::mem::take(&mut messages))
+            }
+            Request::Cancel(key) => {
+                root.request_cancel(&key);
+                Response::Done
+            }
+            Request::Pause(key) => {
+                root.request_pause(&key);
+                Response::Done
+            }
+            Request::Resume(key) => {
+                root.request_resume(&key);
+                Response::Done
+            }
+        };
+        connection.send(&response)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use crate::{
+    messages::Message,
+    progress::{Changes, Key, Task},
+    remote::{serve, Listener, RemoteRoot},
+    Root, Tree,
+};
+use std::{
+    cell::Cell,
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+};
+
+fn names(tasks: &[(Key, Task)]) -> Vec<&str> {
+    tasks.iter().map(|(_, t)| t.name.as_str()).collect()
+}
+
+fn serve_on_loopback(tree: &Tree) -> RemoteRoot {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    serve(tree.clone(), listener);
+    RemoteRoot::connect_tcp(addr).unwrap()
+}
+
+#[test]
+fn remote_roots_mirror_the_served_tree() {
+    let tree = Tree::new();
+    let mut task = tree.add_child("task");
+    task.init(Some(10), Some("items".into()));
+    task.set(4);
+    task.info("hello");
+    let remote = serve_on_loopback(&tree);
+
+    assert_eq!(remote.num_tasks(), 1);
+    assert_eq!(remote.messages_capacity(), tree.messages_capacity());
+
+    let mut tasks = Vec::new();
+    remote.sorted_snapshot(&mut tasks);
+    assert_eq!(names(&tasks), vec!["task"]);
+    assert_eq!(tasks[0].1.progress.as_ref().map(|p| p.step), Some(4));
+
+    let mut messages: Vec<Message> = Vec::new();
+    let state = remote.copy_new_messages(&mut messages, None);
+    assert_eq!(messages.len(), 1);
+    task.info("world");
+    remote.copy_new_messages(&mut messages, Some(state));
+    assert_eq!(
+        messages.iter().map(|m| m.message.as_str()).collect::<Vec<_>>(),
+        vec!["world"],
+        "only new messages are transferred"
+    );
+    remote.copy_messages(&mut messages);
+    assert_eq!(messages.len(), 2);
+}
+
+#[test]
+fn changes_are_forwarded() {
+    let tree = Tree::new();
+    let _a = tree.add_child("a");
+    let remote = serve_on_loopback(&tree);
+
+    let (mut changes, mut tasks) = (Changes::default(), Vec::new());
+    remote.changes_since(None, &mut changes);
+    changes.apply_to(&mut tasks);
+    let _b = tree.add_child("b");
+    remote.changes_since(Some(changes.generation), &mut changes);
+    assert!(!changes.is_complete);
+    assert!(names(&changes.updated).contains(&"b"));
+    changes.apply_to(&mut tasks);
+    assert_eq!(names(&tasks), vec!["a", "b"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_domain_sockets_are_supported() {
+    let dir = std::env::temp_dir().join(format!("prodash-remote-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("socket");
+    let _ = std::fs::remove_file(&path);
+    let tree = Tree::new();
+    let _a = tree.add_child("a");
+    serve(tree.clone(), std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+    let remote = RemoteRoot::connect_unix(&path).unwrap();
+    assert_eq!(remote.num_tasks(), 1);
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn a_lost_connection_leaves_an_empty_root() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let connection = crate::remote::protocol::Connection::handshake(stream, u64::MAX).unwrap();
+        drop(connection);
+    });
+    let remote = RemoteRoot::connect_tcp(addr).unwrap();
+    server.join().unwrap();
+
+    assert_eq!(remote.num_tasks(), 0);
+    assert!(!remote.is_connected());
+    let mut tasks = Vec::new();
+    remote.sorted_snapshot(&mut tasks);
+    assert!(tasks.is_empty());
+}
+
+#[test]
+fn cancellation_can_be_requested_remotely() {
+    let tree = Tree::new();
+    let task = tree.add_child("task");
+    let remote = serve_on_loopback(&tree);
+
+    let mut tasks = Vec::new();
+    remote.sorted_snapshot(&mut tasks);
+    remote.request_cancel(&tasks[0].0);
+    assert!(task.is_cancel_requested());
+    remote.sorted_snapshot(&mut tasks);
+    assert!(tasks[0].1.cancel_requested);
+}
+
+#[test]
+fn tasks_can_be_paused_and_resumed_remotely() {
+    let tree = Tree::new();
+    let task = tree.add_child("task");
+    let remote = serve_on_loopback(&tree);
+
+    let mut tasks = Vec::new();
+    remote.sorted_snapshot(&mut tasks);
+    remote.request_pause(&tasks[0].0);
+    assert!(task.is_paused());
+    remote.request_resume(&tasks[0].0);
+    assert!(!task.is_paused());
+}
+
+/// A listener failing with `error` for the given amount of times before accepting connections.
+struct Flaky {
+    inner: TcpListener,
+    failures: Cell<usize>,
+    error: fn() -> io::Error,
+}
+
+impl Listener for Flaky {
+    type Stream = TcpStream;
+
+    fn accept(&self) -> io::Result<Self::Stream> {
+        if self.failures.get() > 0 {
+            self.failures.set(self.failures.get() - 1);
+            return Err((self.error)());
+        }
+        Listener::accept(&self.inner)
+    }
+}
+
+#[test]
+fn the_server_keeps_accepting_after_transient_errors_and_stops_on_fatal_ones() {
+    let tree = Tree::new();
+    let _a = tree.add_child("a");
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    serve(
+        tree.clone(),
+        Flaky {
+            inner: listener,
+            failures: Cell::new(3),
+            error: || io::Error::from(io::ErrorKind::ConnectionAborted),
+        },
+    );
+    assert_eq!(RemoteRoot::connect_tcp(addr).unwrap().num_tasks(), 1);
+
+    let server = serve(
+        tree,
+        Flaky {
+            inner: TcpListener::bind("127.0.0.1:0").unwrap(),
+            failures: Cell::new(1),
+            error: || io::Error::from(io::ErrorKind::InvalidInput),
+        },
+    );
+    assert_eq!(server.join().unwrap().unwrap_err().kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn connections_sending_overlong_lines_are_closed() {
+    let tree = Tree::new();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    serve(tree, listener);
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream
+        .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+        .unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    stream.write_all(line.as_bytes()).unwrap();
+    let _ = stream.write_all(&vec![b'['; 128 * 1024]);
+
+    line.clear();
+    match reader.read_line(&mut line) {
+        Ok(len) => assert_eq!(len, 0, "the server closed the connection"),
+        Err(err) => assert_eq!(
+            err.kind(),
+            io::ErrorKind::ConnectionReset,
+            "the server closed the connection"
+        ),
+    }
+}
@@ -1,18 +1,20 @@
 use crate::{
     messages::{Message, MessageCopyState, MessageLevel},
-    progress::{self, Value},
+    progress::{self, Outcome, Value},
     unit, Root, Throughput,
 };
 use crosstermion::{
     ansi_term::{ANSIString, ANSIStrings, Color, Style},
     color,
 };
-use std::{collections::VecDeque, io, ops::RangeInclusive};
+use std::{collections::VecDeque, io, ops::RangeInclusive, time::SystemTime};
 use unicode_width::UnicodeWidthStr;
 
 #[derive(Default)]
 pub struct State {
     tree: Vec<(progress::Key, progress::Task)>,
+    changes: progress::Changes,
+    generation: Option<progress::Generation>,
     messages: Vec<Message>,
     for_next_copy: Option<MessageCopyState>,
     /// The size of the message origin, tracking the terminal height so things potentially off screen don't influence width anymore.
@@ -26,12 +28,14 @@ pub struct State {
 
 pub struct Options {
     pub level_filter: Option<RangeInclusive<progress::key::Level>>,
+    pub message_level_filter: Option<Vec<MessageLevel>>,
     pub terminal_dimensions: (u16, u16),
     pub keep_running_if_progress_is_empty: bool,
     pub output_is_terminal: bool,
     pub colored: bool,
     pub timestamp: bool,
     pub hide_cursor: bool,
+    pub elapsed_and_eta: bool,
 }
 
 fn messages(
@@ -40,6 +44,7 @@ fn messages(
     colored: bool,
     max_height: usize,
     timestamp: bool,
+    dropped: usize,
 ) -> io::Result<()> {
     let mut brush = color::Brush::new(colored);
     fn to_color(level: MessageLevel) -> Color {
@@ -48,19 +53,38 @@ fn messages(
             Info => Color::White,
             Success => Color::Green,
             Failure => Color::Red,
+            Warning => Color::Yellow,
+            Debug => Color::Cyan,
         }
     }
     let mut tokens: Vec<ANSIString<'_>> = Vec::with_capacity(6);
     let mut current_maximum = state.message_origin_size.iter().max().cloned().unwrap_or(0);
-    for Message {
-        time,
-        level,
-        origin,
-        message,
-    } in &state.messages
-    {
+    if dropped > 0 {
+        let blocks_drawn_during_previous_tick = state.blocks_per_line.pop_front().unwrap_or(0);
+        tokens.push(" ".into());
+        tokens.push(brush.style(Color::Yellow.dimmed()).paint(format!(
+            "… {} message{} dropped",
+            dropped,
+            if dropped == 1 { "" } else { "s" }
+        )));
+        write!(out, "{}", ANSIStrings(tokens.as_slice()))?;
+        if blocks_drawn_during_previous_tick > block_count_sans_ansi_codes(&tokens) {
+            newline_with_overdraw(out, &tokens, blocks_drawn_during_previous_tick)?;
+        } else {
+            writeln!(out)?;
+        }
+    }
+    for msg in &state.messages {
+        let Message {
+            time,
+            level,
+            message,
+            fields,
+            ..
+        } = msg;
         tokens.clear();
         let blocks_drawn_during_previous_tick = state.blocks_per_line.pop_front().unwrap_or(0);
+        let origin = msg.display_path(" › ").to_string();
         let message_block_len = origin.width();
         current_maximum = current_maximum.max(message_block_len);
         if state.message_origin_size.len() == max_height {
@@ -88,6 +112,9 @@ fn messages(
         )));
         tokens.push(" ".into());
         tokens.push(brush.style(color.bold()).paint(message));
+        for field in fields {
+            tokens.push(brush.style(Style::default().dimmed()).paint(format!(" {}", field)));
+        }
         let message_block_count = block_count_sans_ansi_codes(&tokens);
         write!(out, "{}", ANSIStrings(tokens.as_slice()))?;
 
@@ -107,17 +134,24 @@ pub fn all(
     state: &mut State,
     config: &Options,
 ) -> io::Result<()> {
-    progress.sorted_snapshot(&mut state.tree);
+    progress.changes_since(state.generation, &mut state.changes);
+    state.generation = Some(state.changes.generation);
+    state.changes.apply_to(&mut state.tree);
+    progress::roll_up(&mut state.tree);
     if !config.keep_running_if_progress_is_empty && state.tree.is_empty() {
         return Err(io::Error::new(io::ErrorKind::Other, "stop as progress is empty"));
     }
     state.for_next_copy = Some(progress.copy_new_messages(&mut state.messages, state.for_next_copy.take()));
+    if let Some(levels) = &config.message_level_filter {
+        state.messages.retain(|m| levels.contains(&m.level));
+    }
     messages(
         out,
         state,
         config.colored,
         config.terminal_dimensions.1 as usize,
         config.timestamp,
+        state.for_next_copy.as_ref().map(|s| s.dropped()).unwrap_or(0),
     )?;
 
     if show_progress && config.output_is_terminal {
@@ -144,17 +178,26 @@ pub fn all(
             .filter(|(k, _)| level_range.contains(&k.level()))
             .zip(state.blocks_per_line.iter_mut())
         {
+            let mut counter_throughputs = Vec::with_capacity(value.counters.len());
+            for (index, counter) in value.counters.iter().enumerate() {
+                counter_throughputs.push(
+                    state
+                        .throughput
+                        .as_mut()
+                        .and_then(|tp| tp.update_and_get_counter(key, index, counter)),
+                );
+            }
             max_midpoint = max_midpoint.max(
                 format_progress(
                     key,
                     value,
-                    config.terminal_dimensions.0,
-                    config.colored,
+                    config,
                     state.last_progress_midpoint,
                     state
                         .throughput
                         .as_mut()
                         .and_then(|tp| tp.update_and_get(key, value.progress.as_ref())),
+                    &counter_throughputs,
                     &mut tokens,
                 )
                 .unwrap_or(0),
@@ -264,22 +307,50 @@ fn progress_style(p: &Value) -> Style {
         .normal(),
         Halted(_, _) => Color::Red.dimmed(),
         Blocked(_, _) => Color::Red.normal(),
+        Paused => Color::Cyan.normal(),
+        Finished(Outcome::Succeeded) => Color::Green.dimmed(),
+        Finished(Outcome::Failed) => Color::Purple.normal(),
+        Finished(Outcome::Cancelled) => Color::White.dimmed(),
     }
 }
 
+fn outcome_label(p: &Value) -> Option<&'static str> {
+    match p.state {
+        progress::State::Finished(Outcome::Succeeded) => Some("✓"),
+        progress::State::Finished(Outcome::Failed) => Some("✗"),
+        progress::State::Finished(Outcome::Cancelled) => Some("⊘"),
+        _ => None,
+    }
+}
+
+/// The reason why a task is blocked, halted or paused, along with the time until it continues if known.
+fn blocked_label(p: &Value) -> Option<String> {
+    let (reason, eta, action) = match &p.state {
+        progress::State::Paused => return Some("[paused]".into()),
+        progress::State::Blocked(reason, eta) => (reason, eta, "unblock"),
+        progress::State::Halted(reason, eta) => (reason, eta, "continue"),
+        _ => return None,
+    };
+    let mut label = format!("[{}]", reason);
+    if let Some(remaining) = eta.and_then(|eta| eta.duration_since(SystemTime::now()).ok()) {
+        label.push_str(&format!(" → {} to {}", humantime::format_duration(remaining), action));
+    }
+    Some(label)
+}
+
 fn format_progress<'a>(
     key: &progress::Key,
     value: &'a progress::Task,
-    column_count: u16,
-    colored: bool,
+    config: &Options,
     midpoint: Option<u16>,
     throughput: Option<unit::display::Throughput>,
+    counter_throughputs: &[Option<unit::display::Throughput>],
     buf: &mut Vec<ANSIString<'a>>,
 ) -> Option<u16> {
-    let mut brush = color::Brush::new(colored);
+    let mut brush = color::Brush::new(config.colored);
     buf.clear();
 
-    buf.push(Style::new().paint(format!("{:>level$}", "", level = key.level() as usize)));
+    buf.push(Style::new().paint(format!("{:>level$}", "", level = key.level())));
     match value.progress.as_ref() {
         Some(progress) => {
             let style = progress_style(progress);
@@ -302,6 +373,33 @@ fn format_progress<'a>(
                     }));
                 }
             }
+            if let Some(label) = outcome_label(progress) {
+                buf.push(" ".into());
+                buf.push(brush.style(style.bold()).paint(label));
+            }
+            if let Some(label) = blocked_label(progress) {
+                buf.push(" ".into());
+                buf.push(brush.style(style).paint(label));
+            }
+            if value.cancel_requested && !matches!(progress.state, progress::State::Finished(_)) {
+                buf.push(" ".into());
+                buf.push(brush.style(Color::Yellow.normal()).paint("[cancelling]"));
+            }
+            for (counter, throughput) in value.counters.iter().zip(counter_throughputs) {
+                buf.push(" · ".into());
+                buf.push(
+                    brush
+                        .style(Style::new().bold().dimmed())
+                        .paint(counter.display(*throughput).to_string()),
+                );
+            }
+            if let Some(timing) = Some(progress)
+                .filter(|_| config.elapsed_and_eta)
+                .and_then(|p| crate::time::format_elapsed_and_eta(p, throughput.as_ref()))
+            {
+                buf.push(" ".into());
+                buf.push(brush.style(Style::new().dimmed()).paint(timing));
+            }
             let desired_midpoint = block_count_sans_ansi_codes(buf.as_slice());
             let actual_midpoint = if let Some(midpoint) = midpoint {
                 let padding = midpoint.saturating_sub(desired_midpoint);
@@ -312,9 +410,9 @@ fn format_progress<'a>(
             } else {
                 desired_midpoint
             };
-            let blocks_left = column_count.saturating_sub(actual_midpoint);
+            let blocks_left = config.terminal_dimensions.0.saturating_sub(actual_midpoint);
             if blocks_left > 0 {
-                draw_progress_bar(progress, style, blocks_left, colored, buf);
+                draw_progress_bar(progress, style, blocks_left, config.colored, buf);
             }
             Some(desired_midpoint)
         }
@@ -1,4 +1,4 @@
-use crate::{progress, render::line::draw, Root, Throughput};
+use crate::{messages::MessageLevel, progress, render::line::draw, Root, Throughput};
 use std::{
     io,
     ops::RangeInclusive,
@@ -40,11 +40,22 @@ pub struct Options {
     /// This comes at the cost of additional memory and CPU time.
     pub throughput: bool,
 
+    /// If true, (default false), we will show the time elapsed since each task was initialized, along with
+    /// the estimated time until bounded tasks are done.
+    ///
+    /// The estimate is more accurate if `throughput` is enabled as well.
+    pub elapsed_and_eta: bool,
+
     /// If set, specify all levels that should be shown. Otherwise all available levels are shown.
     ///
     /// This is useful to filter out high-noise lower level progress items in the tree.
     pub level_filter: Option<RangeInclusive<progress::key::Level>>,
 
+    /// If set, specify all levels of messages that should be shown. Otherwise messages of all levels are shown.
+    ///
+    /// This is useful to filter out [debug](crate::messages::MessageLevel::Debug) messages, for example.
+    pub message_level_filter: Option<Vec<MessageLevel>>,
+
     /// If set, progress will only actually be shown after the given duration. Log messages will always be shown without delay.
     ///
     /// This option can be useful to not enforce progress for short actions, causing it to flicker.
@@ -118,9 +129,11 @@ impl Default for Options {
             terminal_dimensions: (80, 20),
             hide_cursor: false,
             level_filter: None,
+            message_level_filter: None,
             initial_delay: None,
             frames_per_second: 6.0,
             throughput: false,
+            elapsed_and_eta: false,
             keep_running_if_progress_is_empty: true,
         }
     }
@@ -192,21 +205,25 @@ pub fn render(
         colored,
         timestamp,
         level_filter,
+        message_level_filter,
         terminal_dimensions,
         initial_delay,
         frames_per_second,
         keep_running_if_progress_is_empty,
         hide_cursor,
         throughput,
+        elapsed_and_eta,
     } = config;
     let config = draw::Options {
         level_filter,
+        message_level_filter,
         terminal_dimensions,
         keep_running_if_progress_is_empty,
         output_is_terminal,
         colored,
         timestamp,
         hide_cursor,
+        elapsed_and_eta,
     };
 
     let (event_send, event_recv) = std::sync::mpsc::sync_channel::<Event>(1);
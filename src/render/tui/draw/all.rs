@@ -24,6 +24,13 @@ pub struct State {
     pub message_offset: u16,
     pub hide_messages: bool,
     pub messages_fullscreen: bool,
+    pub expand_message_fields: bool,
+    pub dropped_messages: usize,
+    pub selected_task: Option<Key>,
+    /// The task that selection, cancellation and pausing apply to, moved with the up and down keys.
+    pub task_cursor: Option<Key>,
+    /// If set, the task offset is adjusted to show the task cursor when drawing next time.
+    pub scroll_to_task_cursor: bool,
     pub user_provided_window_size: Option<Rect>,
     pub duration_per_frame: Duration,
     pub information: Vec<Line>,
@@ -32,6 +39,7 @@ pub struct State {
     pub last_tree_column_width: Option<u16>,
     pub next_tree_column_width: Option<u16>,
     pub throughput: Option<Throughput>,
+    pub elapsed_and_eta: bool,
 }
 
 pub(crate) fn all(
@@ -73,6 +81,18 @@ pub(crate) fn all(
         ),
     );
 
+    let selected_messages: Vec<_>;
+    let (messages, selected_path) = match state.selected_task.as_ref() {
+        Some(selected) => {
+            selected_messages = messages
+                .iter()
+                .filter(|m| m.key.as_ref().map(|key| selected.contains(key)).unwrap_or(false))
+                .cloned()
+                .collect();
+            (selected_messages.as_slice(), Some(name_path(entries, selected)))
+        }
+        None => (messages, None),
+    };
     let (progress_pane, messages_pane) = compute_pane_bounds(
         if state.hide_messages { &[] } else { messages },
         inner_area,
@@ -89,6 +109,8 @@ pub(crate) fn all(
                 ..rect::line_bound(bound, bound.height.saturating_sub(1) as usize)
             },
             &mut state.message_offset,
+            state.expand_message_fields,
+            draw::messages::title(selected_path.as_deref(), state.dropped_messages),
             buf,
         );
     }
@@ -98,6 +120,23 @@ pub(crate) fn all(
     }
 }
 
+/// The names of all tasks from the top-level down to the one with `key`, separated by `›`.
+fn name_path(entries: &[(Key, Task)], key: &Key) -> String {
+    let mut names = Vec::new();
+    let mut key = Some(key.clone());
+    while let Some(current) = key {
+        if let Ok(index) = entries.binary_search_by(|(k, _)| k.cmp(&current)) {
+            names.push(entries[index].1.name.as_str());
+        }
+        key = current.parent();
+    }
+    if names.is_empty() {
+        return "a removed task".into();
+    }
+    names.reverse();
+    names.join(" › ")
+}
+
 fn compute_pane_bounds(messages: &[Message], inner: Rect, messages_fullscreen: bool) -> (Rect, Option<Rect>) {
     if messages.is_empty() {
         (inner, None)
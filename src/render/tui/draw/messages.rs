@@ -1,5 +1,5 @@
 use crate::{
-    messages::{Message, MessageLevel},
+    messages::{Field, Message, MessageLevel},
     render::tui::utils::{block_width, draw_text_with_ellipsis_nowrap, rect, sanitize_offset, VERTICAL_LINE},
     time::{format_time_for_messages, DATE_TIME_HMS},
 };
@@ -13,14 +13,20 @@ use tui::{
 };
 use unicode_width::UnicodeWidthStr;
 
-pub fn pane(messages: &[Message], bound: Rect, overflow_bound: Rect, offset: &mut u16, buf: &mut Buffer) {
+pub fn pane(
+    messages: &[Message],
+    bound: Rect,
+    overflow_bound: Rect,
+    offset: &mut u16,
+    expand_fields: bool,
+    title: Span<'_>,
+    buf: &mut Buffer,
+) {
     let bold = Style::default().add_modifier(Modifier::BOLD);
-    let block = Block::default()
-        .title(Span::styled("Messages", bold))
-        .borders(Borders::TOP);
+    let block = Block::default().title(title).borders(Borders::TOP);
     let inner_bound = block.inner(bound);
     block.render(bound, buf);
-    let help_text = " ⨯ = `| ▢ = ~ ";
+    let help_text = " ⨯ = `| ▢ = ~ | ⊕ = f ";
     draw_text_with_ellipsis_nowrap(rect::snap_to_right(bound, block_width(help_text)), buf, help_text, bold);
 
     let bound = inner_bound;
@@ -38,6 +44,8 @@ pub fn pane(messages: &[Message], bound: Rect, overflow_bound: Rect, offset: &mu
             message,
             level,
             origin,
+            fields,
+            ..
         },
     ) in messages
         .iter()
@@ -64,7 +72,18 @@ pub fn pane(messages: &[Message], bound: Rect, overflow_bound: Rect, offset: &mu
             draw_text_with_ellipsis_nowrap(origin_bound, buf, origin, None);
             draw_text_with_ellipsis_nowrap(rect::offset_x(origin_bound, max_origin_width), buf, "→", None);
         }
-        draw_text_with_ellipsis_nowrap(message_bound, buf, message, None);
+        if fields.is_empty() {
+            draw_text_with_ellipsis_nowrap(message_bound, buf, message, None);
+        } else {
+            let fields_text = format_fields(fields, expand_fields);
+            let width = draw_text_with_ellipsis_nowrap(message_bound, buf, message, None);
+            draw_text_with_ellipsis_nowrap(
+                rect::offset_x(message_bound, width),
+                buf,
+                fields_text,
+                Style::default().add_modifier(Modifier::DIM),
+            );
+        }
     }
 
     if (bound.height as usize) < messages.len().saturating_sub(*offset as usize)
@@ -90,6 +109,33 @@ pub fn pane(messages: &[Message], bound: Rect, overflow_bound: Rect, offset: &mu
     }
 }
 
+/// The title of the messages pane, showing the path of the `selected` task and the amount of `dropped` messages if there are any.
+pub fn title(selected: Option<&str>, dropped: usize) -> Span<'static> {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let title = match selected {
+        Some(path) => format!("Messages of {}", path),
+        None => "Messages".into(),
+    };
+    if dropped == 0 {
+        Span::styled(title, bold)
+    } else {
+        Span::styled(format!("{} ({} dropped)", title, dropped), bold.fg(Color::Yellow))
+    }
+}
+
+/// Show all fields as `key=value` if `expand` is true, or just their amount otherwise.
+fn format_fields(fields: &[Field], expand: bool) -> String {
+    if expand {
+        fields.iter().fold(String::new(), |mut out, field| {
+            out.push(' ');
+            out.push_str(&field.to_string());
+            out
+        })
+    } else {
+        format!(" [+{} field{}]", fields.len(), if fields.len() == 1 { "" } else { "s" })
+    }
+}
+
 const LEVEL_TEXT_WIDTH: u16 = 4;
 fn format_level_column(level: MessageLevel) -> &'static str {
     use MessageLevel::*;
@@ -97,6 +143,8 @@ fn format_level_column(level: MessageLevel) -> &'static str {
         Info => "info",
         Failure => "fail",
         Success => "done",
+        Warning => "warn",
+        Debug => "dbug",
     }
 }
 
@@ -109,6 +157,8 @@ fn level_to_style(level: MessageLevel) -> Style {
             Info => Color::White,
             Failure => Color::Red,
             Success => Color::Green,
+            Warning => Color::Yellow,
+            Debug => Color::Cyan,
         })
 }
 
@@ -1,5 +1,5 @@
 use crate::{
-    progress::{self, Key, Step, Task, Value},
+    progress::{self, Key, Outcome, Step, Task, Value},
     render::tui::{
         draw::State,
         utils::{
@@ -8,7 +8,7 @@ use crate::{
         },
         InterruptDrawInfo,
     },
-    time::format_now_datetime_seconds,
+    time::{format_elapsed_and_eta, format_now_datetime_seconds},
     unit, Throughput,
 };
 use humantime::format_duration;
@@ -24,6 +24,8 @@ use tui::{
 use tui_react::fill_background;
 
 const MIN_TREE_WIDTH: u16 = 20;
+/// Separates the additional counters of a task from its primary progress and from each other.
+const COUNTER_SEPARATOR: &str = " · ";
 
 pub fn pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer, state: &mut State) {
     state.task_offset = sanitize_offset(state.task_offset, entries.len(), bound.height);
@@ -35,6 +37,16 @@ pub fn pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer
             false
         };
     state.task_offset = sanitize_offset(state.task_offset, entries.len(), bound.height);
+    if std::mem::take(&mut state.scroll_to_task_cursor) {
+        if let Some(index) = line_of(entries, state.task_cursor.as_ref()) {
+            let height = bound.height.max(1) as usize;
+            if index < state.task_offset as usize {
+                state.task_offset = index as u16;
+            } else if index >= state.task_offset as usize + height {
+                state.task_offset = (index + 1 - height) as u16;
+            }
+        }
+    }
 
     if entries.is_empty() {
         return;
@@ -50,6 +62,20 @@ pub fn pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer
             };
             let computed = draw_tree(entries, buf, tree_bound, state.task_offset);
             state.last_tree_column_width = Some(computed);
+            for (key, modifier) in [
+                (state.selected_task.as_ref(), Modifier::UNDERLINED),
+                (state.task_cursor.as_ref(), Modifier::REVERSED),
+            ] {
+                if let Some(line) = line_of(entries, key)
+                    .and_then(|index| index.checked_sub(state.task_offset as usize))
+                    .filter(|line| *line < tree_bound.height as usize)
+                {
+                    buf.set_style(
+                        rect::line_bound(tree_bound, line),
+                        Style::default().add_modifier(modifier),
+                    );
+                }
+            }
         } else {
             state.last_tree_column_width = Some(0);
         };
@@ -67,6 +93,7 @@ pub fn pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer
             progress_area,
             state.task_offset,
             state.throughput.as_mut(),
+            state.elapsed_and_eta,
         );
 
         if let Some(tp) = state.throughput.as_mut() {
@@ -91,6 +118,11 @@ pub fn pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer
     }
 }
 
+/// Return the index of the task with the given `key` in `entries`, if it is there.
+fn line_of(entries: &[(Key, Task)], key: Option<&Key>) -> Option<usize> {
+    key.and_then(|key| entries.binary_search_by(|(k, _)| k.cmp(key)).ok())
+}
+
 pub(crate) fn headline(
     entries: &[(Key, Task)],
     interrupt_mode: InterruptDrawInfo,
@@ -98,19 +130,22 @@ pub(crate) fn headline(
     buf: &mut Buffer,
     bound: Rect,
 ) {
-    let (num_running_tasks, num_blocked_tasks, num_groups) = entries.iter().fold(
-        (0, 0, 0),
-        |(mut running, mut blocked, mut groups), (_key, Task { progress, .. })| {
-            match progress.as_ref().map(|p| p.state) {
+    let (num_running_tasks, num_blocked_tasks, num_finished_tasks, num_groups) = entries.iter().fold(
+        (0, 0, 0, 0),
+        |(mut running, mut blocked, mut finished, mut groups), (_key, Task { progress, .. })| {
+            match progress.as_ref().map(|p| &p.state) {
                 Some(progress::State::Running) => running += 1,
-                Some(progress::State::Blocked(_, _)) | Some(progress::State::Halted(_, _)) => blocked += 1,
+                Some(progress::State::Blocked(_, _))
+                | Some(progress::State::Halted(_, _))
+                | Some(progress::State::Paused) => blocked += 1,
+                Some(progress::State::Finished(_)) => finished += 1,
                 None => groups += 1,
             }
-            (running, blocked, groups)
+            (running, blocked, finished, groups)
         },
     );
     let text = format!(
-        " {} {} {:3} running + {:3} blocked + {:3} groups = {} ",
+        " {} {} {:3} running + {:3} blocked + {:3} finished + {:3} groups = {} ",
         match interrupt_mode {
             InterruptDrawInfo::Instantly => "'q' or CTRL+c to quit",
             InterruptDrawInfo::Deferred(interrupt_requested) => {
@@ -132,6 +167,7 @@ pub(crate) fn headline(
         },
         num_running_tasks,
         num_blocked_tasks,
+        num_finished_tasks,
         num_groups,
         entries.len()
     );
@@ -174,35 +210,72 @@ pub fn draw_progress(
     bound: Rect,
     offset: u16,
     mut throughput: Option<&mut Throughput>,
+    elapsed_and_eta: bool,
 ) {
     let title_spacing = 2u16 + 1; // 2 on the left, 1 on the right
-    let max_progress_label_width = entries
-        .iter()
-        .skip(offset as usize)
-        .take(bound.height as usize)
-        .map(|(_, Task { progress, .. })| progress)
-        .fold(0, |state, progress| match progress {
+    let max_progress_label_width = entries.iter().skip(offset as usize).take(bound.height as usize).fold(
+        0,
+        |state, (_, Task { progress, counters, .. })| match progress {
             progress @ Some(_) => {
                 use std::io::Write;
                 let mut w = GraphemeCountWriter::default();
                 write!(w, "{}", ProgressFormat(progress, 0, None)).expect("never fails");
+                if let Some(timing) = progress
+                    .as_ref()
+                    .filter(|_| elapsed_and_eta)
+                    .and_then(|p| format_elapsed_and_eta(p, None))
+                {
+                    write!(w, " {}", timing).expect("never fails");
+                }
+                for counter in counters {
+                    write!(w, "{}{}", COUNTER_SEPARATOR, counter.display(None)).expect("never fails");
+                }
                 state.max(w.0)
             }
             None => state,
-        });
+        },
+    );
 
-    for (line, (entry_index, (key, Task { progress, name: title }))) in entries
+    for (
+        line,
+        (
+            entry_index,
+            (
+                key,
+                Task {
+                    progress,
+                    name: title,
+                    counters,
+                    cancel_requested,
+                    ..
+                },
+            ),
+        ),
+    ) in entries
         .iter()
         .enumerate()
         .skip(offset as usize)
         .take(bound.height as usize)
         .enumerate()
     {
+        let counters_text = progress.as_ref().map(|_| {
+            counters
+                .iter()
+                .enumerate()
+                .fold(String::new(), |mut out, (index, counter)| {
+                    let throughput = throughput
+                        .as_mut()
+                        .and_then(|tp| tp.update_and_get_counter(key, index, counter));
+                    out.push_str(COUNTER_SEPARATOR);
+                    out.push_str(&counter.display(throughput).to_string());
+                    out
+                })
+        });
         let throughput = throughput
             .as_mut()
             .and_then(|tp| tp.update_and_get(key, progress.as_ref()));
         let line_bound = rect::line_bound(bound, line);
-        let progress_text = format!(
+        let mut progress_text = format!(
             " {progress}",
             progress = ProgressFormat(
                 progress,
@@ -214,19 +287,35 @@ pub fn draw_progress(
                 throughput
             )
         );
+        if let Some(timing) = progress
+            .as_ref()
+            .filter(|_| elapsed_and_eta)
+            .and_then(|p| format_elapsed_and_eta(p, throughput.as_ref()))
+        {
+            progress_text.push(' ');
+            progress_text.push_str(&timing);
+        }
+        if let Some(counters_text) = counters_text {
+            progress_text.push_str(&counters_text);
+        }
+        if *cancel_requested && !matches!(progress.as_ref().map(|p| &p.state), Some(progress::State::Finished(_))) {
+            progress_text.push_str(" [cancelling]");
+        }
 
         draw_text_with_ellipsis_nowrap(line_bound, buf, VERTICAL_LINE, None);
 
         let tree_prefix = level_prefix(entries, entry_index);
         let progress_rect = rect::offset_x(line_bound, block_width(&tree_prefix) as u16);
         draw_text_with_ellipsis_nowrap(line_bound, buf, tree_prefix, None);
-        match progress.as_ref().map(|p| (p.fraction(), p.state, p.step)) {
+        match progress.as_ref().map(|p| (p.fraction(), &p.state, p.step)) {
             Some((Some(fraction), state, _step)) => {
                 let mut progress_text = progress_text;
                 add_block_eta(state, &mut progress_text);
                 let (bound, style) = draw_progress_bar_fn(buf, progress_rect, fraction, |fraction| match state {
                     progress::State::Blocked(_, _) => Color::Red,
                     progress::State::Halted(_, _) => Color::LightRed,
+                    progress::State::Paused => Color::Cyan,
+                    progress::State::Finished(outcome) => finished_color(*outcome),
                     progress::State::Running => {
                         if fraction >= 0.8 {
                             Color::Green
@@ -257,6 +346,8 @@ pub fn draw_progress(
                     match state {
                         progress::State::Blocked(_, _) => Color::Red,
                         progress::State::Halted(_, _) => Color::LightRed,
+                        progress::State::Paused => Color::Cyan,
+                        progress::State::Finished(outcome) => finished_color(*outcome),
                         progress::State::Running => Color::White,
                     },
                 );
@@ -270,13 +361,13 @@ pub fn draw_progress(
     }
 }
 
-fn add_block_eta(state: progress::State, progress_text: &mut String) {
+fn add_block_eta(state: &progress::State, progress_text: &mut String) {
     match state {
         progress::State::Blocked(reason, maybe_eta) | progress::State::Halted(reason, maybe_eta) => {
             progress_text.push_str(" [");
             progress_text.push_str(reason);
             progress_text.push(']');
-            if let Some(eta) = maybe_eta {
+            if let Some(eta) = *maybe_eta {
                 let now = SystemTime::now();
                 if eta > now {
                     progress_text.push_str(&format!(
@@ -291,10 +382,26 @@ fn add_block_eta(state: progress::State, progress_text: &mut String) {
                 }
             }
         }
+        progress::State::Paused => progress_text.push_str(" [paused]"),
+        progress::State::Finished(outcome) => {
+            progress_text.push_str(match outcome {
+                Outcome::Succeeded => " [succeeded]",
+                Outcome::Failed => " [failed]",
+                Outcome::Cancelled => " [cancelled]",
+            });
+        }
         progress::State::Running => {}
     }
 }
 
+fn finished_color(outcome: Outcome) -> Color {
+    match outcome {
+        Outcome::Succeeded => Color::DarkGray,
+        Outcome::Failed => Color::Magenta,
+        Outcome::Cancelled => Color::Gray,
+    }
+}
+
 fn draw_spinner(buf: &mut Buffer, bound: Rect, step: Step, seed: usize, color: Color) {
     if bound.width == 0 {
         return;
@@ -378,10 +485,9 @@ pub fn draw_tree(entries: &[(Key, Task)], buf: &mut Buffer, bound: Rect, offset:
 
 fn level_prefix(entries: &[(Key, Task)], entry_index: usize) -> String {
     let adj = Key::adjacency(entries, entry_index);
-    let key = entries[entry_index].0;
-    let key_level = key.level();
+    let key_level = entries[entry_index].0.level();
     let is_orphan = adj.level() != key_level;
-    let mut buf = String::with_capacity(key_level as usize);
+    let mut buf = String::with_capacity(key_level);
     for level in 1..=key_level {
         use crate::progress::key::SiblingLocation::*;
         let is_child_level = level == key_level;
@@ -471,7 +577,7 @@ pub fn draw_overflow(
         label,
         color_text_according_to_progress,
     );
-    let help_text = "⇊ = d|↓ = j|⇈ = u|↑ = k ";
+    let help_text = "◉ = s|⊘ = c|⏸ = p|⇊ = d|↓ = j|⇈ = u|↑ = k ";
     draw_text_nowrap_fn(
         rect::snap_to_right(bound, block_width(help_text)),
         buf,
@@ -27,6 +27,12 @@ pub struct Options {
     /// This comes at the cost of additional memory and CPU time.
     pub throughput: bool,
 
+    /// If true, (default false), we will show the time elapsed since each task was initialized, along with
+    /// the estimated time until bounded tasks are done.
+    ///
+    /// The estimate is more accurate if `throughput` is enabled as well.
+    pub elapsed_and_eta: bool,
+
     /// If set, recompute the column width of the task tree only every given frame. Otherwise the width will be recomputed every frame.
     ///
     /// Use this if there are many short-running tasks with varying names paired with high refresh rates of multiple frames per second to
@@ -53,6 +59,7 @@ impl Default for Options {
             title: "Progress Dashboard".into(),
             frames_per_second: 10.0,
             throughput: false,
+            elapsed_and_eta: false,
             recompute_column_width_every_nth_frame: None,
             window_size: None,
             stop_if_empty_progress: false,
@@ -119,6 +126,9 @@ pub enum Event {
     SetInterruptMode(Interrupt),
 }
 
+/// The amount of messages to load from the message history at a time when scrolling back.
+const MESSAGE_HISTORY_PAGE_SIZE: usize = 100;
+
 /// Returns a future that draws the terminal user interface indefinitely.
 ///
 /// * `progress` is the progress tree whose information to visualize.
@@ -139,6 +149,7 @@ pub fn render_with_input(
         window_size,
         recompute_column_width_every_nth_frame,
         throughput,
+        elapsed_and_eta,
         stop_if_empty_progress,
     } = options;
     let mut terminal = new_terminal(AlternateRawScreen::try_from(out)?)?;
@@ -151,14 +162,19 @@ pub fn render_with_input(
         let mut state = draw::State {
             title,
             duration_per_frame,
+            elapsed_and_eta,
             ..draw::State::default()
         };
         if throughput {
             state.throughput = Some(Throughput::default());
         }
         let mut interrupt_mode = InterruptDrawInfo::Instantly;
-        let mut entries = Vec::with_capacity(progress.num_tasks());
-        let mut messages = Vec::with_capacity(progress.messages_capacity());
+        let mut entries: Vec<(crate::progress::Key, crate::progress::Task)> = Vec::with_capacity(progress.num_tasks());
+        let (mut changes, mut generation) = (crate::progress::Changes::default(), None);
+        let messages_capacity = progress.messages_capacity();
+        let mut messages = Vec::with_capacity(messages_capacity);
+        let (mut new_messages, mut message_copy_state) = (Vec::new(), None);
+        let (mut messages_limit, mut message_history_exhausted) = (messages_capacity, false);
         let mut events = ticker(duration_per_frame)
             .map(|_| Event::Tick)
             .or(key_receive.map(Event::Input))
@@ -177,6 +193,30 @@ pub fn render_with_input(
                     },
                     Key::Char('`') => state.hide_messages = !state.hide_messages,
                     Key::Char('~') => state.messages_fullscreen = !state.messages_fullscreen,
+                    Key::Char('f') => state.expand_message_fields = !state.expand_message_fields,
+                    Key::Up => move_task_cursor(&mut state, &entries, -1),
+                    Key::Down => move_task_cursor(&mut state, &entries, 1),
+                    Key::Char('s') => {
+                        state.selected_task = if state.selected_task == state.task_cursor {
+                            None
+                        } else {
+                            state.task_cursor.clone()
+                        };
+                    }
+                    Key::Char('c') => {
+                        if let Some(key) = state.task_cursor.as_ref() {
+                            progress.request_cancel(key);
+                        }
+                    }
+                    Key::Char('p') => {
+                        if let Some(key) = state.task_cursor.as_ref() {
+                            if is_paused(&entries, key) {
+                                progress.request_resume(key);
+                            } else {
+                                progress.request_pause(key);
+                            }
+                        }
+                    }
                     Key::Char('J') => state.message_offset = state.message_offset.saturating_add(1),
                     Key::Char('D') => state.message_offset = state.message_offset.saturating_add(10),
                     Key::Char('j') => state.task_offset = state.task_offset.saturating_add(1),
@@ -210,7 +250,10 @@ pub fn render_with_input(
             if !skip_redraw {
                 tick += 1;
 
-                progress.sorted_snapshot(&mut entries);
+                progress.changes_since(generation, &mut changes);
+                generation = Some(changes.generation);
+                changes.apply_to(&mut entries);
+                crate::progress::roll_up(&mut entries);
                 if stop_if_empty_progress && entries.is_empty() {
                     break;
                 }
@@ -221,7 +264,28 @@ pub fn render_with_input(
                     .unwrap_or(terminal_window_size);
                 let buf = terminal.current_buffer_mut();
                 if !state.hide_messages {
-                    progress.copy_messages(&mut messages);
+                    let copy_state = progress.copy_new_messages(&mut new_messages, message_copy_state.take());
+                    state.dropped_messages += copy_state.dropped();
+                    message_copy_state = Some(copy_state);
+                    // New messages push older ones out, which the history can provide again.
+                    message_history_exhausted &= new_messages.is_empty();
+                    messages.append(&mut new_messages);
+                    messages.drain(..messages.len().saturating_sub(messages_limit));
+
+                    // Scrolling to the oldest message we have loads more from the message history, if there is any.
+                    if state.message_offset > 0
+                        && !message_history_exhausted
+                        && state.message_offset as usize + window_size.height as usize >= messages.len()
+                    {
+                        progress.copy_message_history(&mut new_messages, messages.len() + MESSAGE_HISTORY_PAGE_SIZE);
+                        if new_messages.len() > messages.len() {
+                            messages_limit = new_messages.len().max(messages_capacity);
+                            std::mem::swap(&mut messages, &mut new_messages);
+                        } else {
+                            message_history_exhausted = true;
+                        }
+                        new_messages.clear();
+                    }
                 }
 
                 draw::all(&mut state, interrupt_mode, &entries, &messages, window_size, buf);
@@ -238,6 +302,33 @@ pub fn render_with_input(
     Ok(render_fut)
 }
 
+/// Move the task cursor by `delta` tasks, or put it on the topmost visible task if there is none yet.
+fn move_task_cursor(state: &mut draw::State, entries: &[(crate::progress::Key, crate::progress::Task)], delta: isize) {
+    if entries.is_empty() {
+        return;
+    }
+    let index = match state.task_cursor.as_ref() {
+        Some(cursor) => match entries.binary_search_by(|(k, _)| k.cmp(cursor)) {
+            Ok(index) => (index as isize).saturating_add(delta).max(0) as usize,
+            // The task is gone, so move to the one that took its place.
+            Err(index) => index,
+        },
+        None => state.task_offset as usize,
+    };
+    state.task_cursor = entries.get(index.min(entries.len() - 1)).map(|(key, _)| key.clone());
+    state.scroll_to_task_cursor = true;
+}
+
+/// Returns true if the first task at or below `key` which can make progress is paused.
+fn is_paused(entries: &[(crate::progress::Key, crate::progress::Task)], key: &crate::progress::Key) -> bool {
+    entries
+        .iter()
+        .filter(|(k, _)| key.contains(k))
+        .find_map(|(_, task)| task.progress.as_ref())
+        .map(|p| p.state == crate::progress::State::Paused)
+        .unwrap_or(false)
+}
+
 /// An easy-to-use version of `render_with_input(…)` that does not allow state manipulation via an event stream.
 pub fn render(
     out: impl std::io::Write,
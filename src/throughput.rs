@@ -72,7 +72,8 @@ impl State {
 /// A utility to compute throughput of a set of progress values usually available to a renderer.
 #[derive(Default)]
 pub struct Throughput {
-    sorted_by_key: Vec<(progress::Key, State)>,
+    /// The state of each task's primary value (`None`) and its additional counters (`Some(index)`).
+    sorted_by_key: Vec<((progress::Key, Option<usize>), State)>,
     updated_at: Option<SystemTime>,
     elapsed: Option<Duration>,
 }
@@ -93,17 +94,39 @@ impl Throughput {
         key: &progress::Key,
         progress: Option<&progress::Value>,
     ) -> Option<unit::display::Throughput> {
-        progress.and_then(|progress| {
-            self.elapsed
-                .and_then(|elapsed| match self.sorted_by_key.binary_search_by_key(key, |t| t.0) {
-                    Ok(index) => self.sorted_by_key[index].1.update(progress.step, elapsed),
-                    Err(index) => {
-                        let state = State::new(progress.step, elapsed);
-                        let tp = state.throughput();
-                        self.sorted_by_key.insert(index, (*key, state));
-                        tp
-                    }
-                })
+        progress.and_then(|progress| self.update_step(key, None, progress.step))
+    }
+
+    /// Lookup or create the counter at `index` of the task at `key` and set its current step, returning its computed
+    /// throughput.
+    pub fn update_and_get_counter(
+        &mut self,
+        key: &progress::Key,
+        index: usize,
+        counter: &progress::Counter,
+    ) -> Option<unit::display::Throughput> {
+        self.update_step(key, Some(index), counter.step)
+    }
+
+    fn update_step(
+        &mut self,
+        key: &progress::Key,
+        counter: Option<usize>,
+        step: progress::Step,
+    ) -> Option<unit::display::Throughput> {
+        self.elapsed.and_then(|elapsed| {
+            match self
+                .sorted_by_key
+                .binary_search_by(|((k, c), _)| k.cmp(key).then(c.cmp(&counter)))
+            {
+                Ok(index) => self.sorted_by_key[index].1.update(step, elapsed),
+                Err(index) => {
+                    let state = State::new(step, elapsed);
+                    let tp = state.throughput();
+                    self.sorted_by_key.insert(index, ((key.clone(), counter), state));
+                    tp
+                }
+            }
         })
     }
 
@@ -113,6 +136,6 @@ impl Throughput {
     /// progress.
     pub fn reconcile(&mut self, sorted_values: &[(progress::Key, progress::Task)]) {
         self.sorted_by_key
-            .retain(|(key, _)| sorted_values.binary_search_by_key(key, |e| e.0).is_ok());
+            .retain(|((key, _), _)| sorted_values.binary_search_by(|e| e.0.cmp(key)).is_ok());
     }
 }
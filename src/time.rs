@@ -61,3 +61,23 @@ pub use localtime::*;
 
 #[cfg(not(feature = "local-time"))]
 pub use utc::*;
+
+/// Return the elapsed time of `progress` along with the estimated time until it is done, if known,
+/// or `None` if there is nothing to show.
+#[cfg(feature = "humantime")]
+pub(crate) fn format_elapsed_and_eta(
+    progress: &crate::progress::Value,
+    throughput: Option<&crate::unit::display::Throughput>,
+) -> Option<String> {
+    fn format_seconds(d: std::time::Duration) -> humantime::FormattedDuration {
+        humantime::format_duration(std::time::Duration::from_secs(d.as_secs()))
+    }
+    if let crate::progress::State::Finished(_) = progress.state {
+        return None;
+    }
+    let elapsed = progress.elapsed()?;
+    Some(match progress.eta(throughput) {
+        Some(eta) => format!("{} elapsed, ETA {}", format_seconds(elapsed), format_seconds(eta)),
+        None => format!("{} elapsed", format_seconds(elapsed)),
+    })
+}
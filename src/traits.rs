@@ -1,4 +1,7 @@
-use crate::{messages::MessageLevel, progress, Unit};
+use crate::{
+    messages::{Field, MessageLevel},
+    progress, Unit,
+};
 use std::time::Instant;
 
 /// A trait for describing hierarchical process.
@@ -42,6 +45,47 @@ pub trait Progress: Send + 'static {
         None
     }
 
+    /// Set the maximum to `max`, without altering the current step, and return the previous maximum.
+    ///
+    /// Use this, as opposed to `init(…)`, to change the upper bound while progress is being made, e.g. as more work is discovered.
+    /// If `max` is `None`, the progress becomes unbounded.
+    ///
+    /// The default implementation does nothing and returns the current maximum, as `init(…)` would reset the state.
+    fn set_max(&mut self, _max: Option<progress::Step>) -> Option<progress::Step> {
+        self.max()
+    }
+
+    /// Increment the maximum by `step`, without altering the current step.
+    ///
+    /// If the progress is unbounded, it becomes bounded with `step` as maximum.
+    ///
+    /// The default implementation calls `set_max(…)`.
+    fn inc_max(&mut self, step: progress::Step) {
+        let max = self.max().unwrap_or(0).saturating_add(step);
+        self.set_max(Some(max));
+    }
+
+    /// Returns true if this task was asked to cancel, typically by a user through a renderer and [`Root::request_cancel()`].
+    ///
+    /// Poll this regularly to stop work early and acknowledge the request by finishing the task.
+    /// The default implementation returns false.
+    fn is_cancel_requested(&self) -> bool {
+        false
+    }
+
+    /// Returns true if this task was asked to pause, typically by a user through a renderer and [`Root::request_pause()`].
+    ///
+    /// The default implementation returns false.
+    fn is_paused(&self) -> bool {
+        false
+    }
+
+    /// Block the current thread while this task is [paused](#method.is_paused), to let users throttle work by hand.
+    /// Implementations return early once [cancellation is requested](#method.is_cancel_requested).
+    ///
+    /// The default implementation returns immediately.
+    fn wait_if_paused(&self) {}
+
     /// Returns the current step, as controlled by `inc*(…)` calls
     fn step(&self) -> progress::Step;
 
@@ -69,6 +113,15 @@ pub trait Progress: Send + 'static {
     /// made, including indicating success or failure.
     fn message(&mut self, level: MessageLevel, message: impl Into<String>);
 
+    /// Create a `message` of the given `level` along with typed key/value `fields`, and store it with the progress tree.
+    ///
+    /// Use this to attach machine-readable details like paths or error codes.
+    /// The default implementation ignores `fields` and calls [`message(…)`](#tymethod.message).
+    fn message_with_fields(&mut self, level: MessageLevel, message: impl Into<String>, fields: Vec<Field>) {
+        let _ = fields;
+        self.message(level, message)
+    }
+
     /// Create a message providing additional information about the progress thus far.
     fn info(&mut self, message: impl Into<String>) {
         self.message(MessageLevel::Info, message)
@@ -81,6 +134,14 @@ pub trait Progress: Send + 'static {
     fn fail(&mut self, message: impl Into<String>) {
         self.message(MessageLevel::Failure, message)
     }
+    /// Create a message indicating a recoverable problem which doesn't cause the task to fail
+    fn warn(&mut self, message: impl Into<String>) {
+        self.message(MessageLevel::Warning, message)
+    }
+    /// Create a message with details that are typically only of interest when investigating issues
+    fn debug(&mut self, message: impl Into<String>) {
+        self.message(MessageLevel::Debug, message)
+    }
     /// A shorthand to print throughput information
     fn show_throughput(&mut self, start: Instant) {
         let step = self.step();
@@ -142,11 +203,61 @@ pub trait Root {
     /// The `out` vec will be cleared automatically.
     fn sorted_snapshot(&self, out: &mut Vec<(progress::Key, progress::Task)>);
 
+    /// Obtain all tasks that were added, changed or removed since the given `generation` and store them in `out`,
+    /// which is cleared beforehand. Pass `None` to obtain all tasks.
+    ///
+    /// This allows to keep a copy of the progress tree up to date with [`Changes::apply_to(…)`][progress::Changes::apply_to()]
+    /// without copying and sorting all tasks each time.
+    ///
+    /// The default implementation provides a complete [sorted snapshot](#tymethod.sorted_snapshot) each time.
+    fn changes_since(&self, generation: Option<progress::Generation>, out: &mut progress::Changes) {
+        let _ = generation;
+        out.removed.clear();
+        out.is_complete = true;
+        out.generation = 0;
+        self.sorted_snapshot(&mut out.updated);
+    }
+
     /// Copy all messages from the internal ring buffer into the given `out`
     /// vector. Messages are ordered from oldest to newest.
     fn copy_messages(&self, out: &mut Vec<Message>);
 
     /// Copy only new messages from the internal ring buffer into the given `out`
     /// vector. Messages are ordered from oldest to newest.
+    ///
+    /// The returned state reports how many messages were [dropped](crate::messages::MessageCopyState::dropped())
+    /// because they were overwritten before they could be copied.
     fn copy_new_messages(&self, out: &mut Vec<Message>, prev: Option<MessageCopyState>) -> MessageCopyState;
+
+    /// Copy up to `max` of the most recent messages into the given `out` vector, possibly including ones that aren't
+    /// in the internal ring buffer anymore. Messages are ordered from oldest to newest.
+    ///
+    /// The default implementation [copies all messages](#tymethod.copy_messages) and keeps the `max` most recent ones.
+    fn copy_message_history(&self, out: &mut Vec<Message>, max: usize) {
+        self.copy_messages(out);
+        out.drain(..out.len().saturating_sub(max));
+    }
+
+    /// Ask the task at `key` and all of its children to cancel, which they observe through
+    /// [`Progress::is_cancel_requested()`].
+    ///
+    /// The default implementation does nothing.
+    fn request_cancel(&self, key: &progress::Key) {
+        let _ = key;
+    }
+
+    /// Ask the task at `key` and all of its children to pause until they are [resumed](#method.request_resume),
+    /// which they observe through [`Progress::is_paused()`] and [`Progress::wait_if_paused()`].
+    ///
+    /// The default implementation does nothing.
+    fn request_pause(&self, key: &progress::Key) {
+        let _ = key;
+    }
+
+    /// Resume the task at `key` and all of its children after they were [paused](#method.request_pause).
+    ///
+    /// The default implementation does nothing.
+    fn request_resume(&self, key: &progress::Key) {
+        let _ = key;
+    }
 }
@@ -0,0 +1,78 @@
+use crate::progress::{Generation, Key};
+use parking_lot::Mutex;
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// The amount of removed keys we remember before resorting to complete snapshots for consumers that fall behind.
+const MAX_REMOVALS: usize = 4096;
+
+/// Keeps track of the current generation of a tree and of the keys that were removed from it.
+#[derive(Debug)]
+pub(crate) struct ChangeTracker {
+    generation: AtomicU64,
+    removals: Mutex<Removals>,
+}
+
+#[derive(Clone, Default, Debug)]
+struct Removals {
+    keys: VecDeque<(Generation, Key)>,
+    /// The generation of the most recent removal that we forgot about.
+    forgotten_until: Option<Generation>,
+}
+
+impl Default for ChangeTracker {
+    fn default() -> Self {
+        ChangeTracker {
+            generation: AtomicU64::new(1),
+            removals: Default::default(),
+        }
+    }
+}
+
+impl ChangeTracker {
+    /// The generation to stamp changes with.
+    pub(crate) fn current(&self) -> Generation {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    /// Start a new generation and return the one that was current until now.
+    pub(crate) fn advance(&self) -> Generation {
+        self.generation.fetch_add(1, Ordering::Relaxed)
+    }
+
+    pub(crate) fn deep_clone(&self) -> ChangeTracker {
+        ChangeTracker {
+            generation: AtomicU64::new(self.current()),
+            removals: Mutex::new(self.removals.lock().clone()),
+        }
+    }
+
+    pub(crate) fn record_removal(&self, key: Key) {
+        let mut removals = self.removals.lock();
+        // Obtain the generation while holding the lock to keep removals sorted by generation.
+        let generation = self.current();
+        if removals.keys.len() == MAX_REMOVALS {
+            removals.forgotten_until = removals.keys.pop_front().map(|(generation, _)| generation);
+        }
+        removals.keys.push_back((generation, key));
+    }
+
+    /// Add all keys removed since `generation` to `out`, or return false if some of them were forgotten.
+    pub(crate) fn removals_since(&self, generation: Generation, out: &mut Vec<Key>) -> bool {
+        let removals = self.removals.lock();
+        if removals.forgotten_until.map(|g| g >= generation).unwrap_or(false) {
+            return false;
+        }
+        out.extend(
+            removals
+                .keys
+                .iter()
+                .rev()
+                .take_while(|(removed_at, _)| *removed_at >= generation)
+                .map(|(_, key)| key.clone()),
+        );
+        true
+    }
+}
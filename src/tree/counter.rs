@@ -0,0 +1,43 @@
+use crate::progress::Step;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+/// A shared and lock-free handle to the step of a [`tree::Item`][crate::tree::Item], as obtained by
+/// [`Item::counter()`][crate::tree::Item::counter()].
+///
+/// It's cheap to clone and can be used from many threads at once, which makes it suitable for hot loops
+/// that would otherwise spend considerable time acquiring locks for each call to `inc()`.
+/// Renderers pick up the latest step when taking their next snapshot.
+///
+/// **Note** that changes only show if the `Item` was [initialized](crate::tree::Item::init()), and that the
+/// counter doesn't alter the state of the `Item`, which is what [`Item::set(…)`][crate::tree::Item::set()] does.
+#[derive(Clone, Default, Debug)]
+pub struct StepCounter(Arc<AtomicUsize>);
+
+impl StepCounter {
+    /// Set the current progress to the given `step`.
+    pub fn set(&self, step: Step) {
+        self.0.store(step, Ordering::Relaxed);
+    }
+
+    /// Increment the current progress by the given `step`.
+    pub fn inc_by(&self, step: Step) {
+        self.0.fetch_add(step, Ordering::Relaxed);
+    }
+
+    /// Increment the current progress by one.
+    pub fn inc(&self) {
+        self.inc_by(1)
+    }
+
+    /// Returns the current step.
+    pub fn get(&self) -> Step {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn deep_clone(&self) -> StepCounter {
+        StepCounter(Arc::new(AtomicUsize::new(self.get())))
+    }
+}
@@ -1,11 +1,51 @@
 use crate::{
-    messages::{MessageLevel, MessageRingBuffer},
-    progress::{key, Key, State, Step, Task, Value},
+    messages::{Field, Message, MessageLevel, MessageRingBuffer},
+    progress::{key, Counter, Generation, Key, Outcome, RollUp, State, Step, Task, Value},
+    tree::{ChangeTracker, KeepFinished, PauseFlag, SinkWriter, StepCounter, WaitIfPaused},
     unit::Unit,
 };
-use dashmap::DashMap;
+use dashmap::{mapref::entry::Entry, DashMap};
 use parking_lot::Mutex;
-use std::{ops::Deref, sync::Arc, time::SystemTime};
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Instant, SystemTime},
+};
+
+/// The target of the records messages are forwarded to `log` with.
+#[cfg(feature = "progress-tree-log")]
+pub(crate) const LOG_TARGET: &str = module_path!();
+
+/// Forward `message` to `log` if the `progress-tree-log` feature is enabled.
+#[cfg_attr(not(feature = "progress-tree-log"), allow(unused_variables))]
+pub(crate) fn log_message(message: &Message) {
+    #[cfg(feature = "progress-tree-log")]
+    crate::messages::log_with_fields(
+        LOG_TARGET,
+        match message.level {
+            MessageLevel::Failure | MessageLevel::Warning => log::Level::Warn,
+            MessageLevel::Info | MessageLevel::Success => log::Level::Info,
+            MessageLevel::Debug => log::Level::Debug,
+        },
+        &message.fields,
+        format_args!("{} → {}", message.origin, message.message),
+    );
+}
+
+/// Pause or resume the task at `key` and all of its children in `tree`, marking those that changed.
+///
+/// Nodes are only locked for reading, as their pause flags can be changed through shared references.
+pub(crate) fn set_paused(tree: &DashMap<Key, Node>, changes: &ChangeTracker, key: &Key, paused: bool) {
+    let generation = changes.current();
+    for r in tree.iter() {
+        if key.contains(r.key()) && r.value().pause.set(paused) {
+            r.value().mark_changed(generation);
+        }
+    }
+}
 
 /// A `Tree` represents an element of the progress tree.
 ///
@@ -28,13 +68,207 @@ use std::{ops::Deref, sync::Arc, time::SystemTime};
 pub struct Item {
     pub(crate) key: Key,
     pub(crate) highest_child_id: key::Id,
-    pub(crate) tree: Arc<DashMap<Key, Task>>,
+    pub(crate) tree: Arc<DashMap<Key, Node>>,
     pub(crate) messages: Arc<Mutex<MessageRingBuffer>>,
+    pub(crate) message_sink: Option<Arc<Mutex<SinkWriter>>>,
+    pub(crate) step: StepCounter,
+    pub(crate) keep_finished: Option<KeepFinished>,
+    pub(crate) changes: Arc<ChangeTracker>,
+    pub(crate) cancel_requested: Arc<AtomicBool>,
+    pub(crate) pause: Arc<PauseFlag>,
+    /// Our finished children kept with [`KeepFinished::UntilParentDrops`].
+    pub(crate) finished_children: Arc<Mutex<FinishedChildren>>,
+    /// The `finished_children` of our parent.
+    pub(crate) finished_siblings: Arc<Mutex<FinishedChildren>>,
+    /// All finished tasks kept with [`KeepFinished::For`] along with the time they finished, oldest first.
+    pub(crate) finished: Arc<Mutex<VecDeque<(Instant, Key)>>>,
+}
+
+/// The finished children of an `Item` which are kept until it is dropped.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct FinishedChildren {
+    /// The keys of the children, oldest first.
+    keys: VecDeque<Key>,
+    /// Set once the `Item` was dropped, after which its children are removed as soon as they finish.
+    parent_dropped: bool,
+}
+
+/// A task as stored in the tree, with its step kept separately so it can be changed without locking.
+#[derive(Debug)]
+pub(crate) struct Node {
+    pub(crate) task: Task,
+    pub(crate) step: StepCounter,
+    /// The steps of the additional counters of our task, in the same order.
+    pub(crate) counters: Vec<StepCounter>,
+    /// The steps seen when we were last checked for changes, our own first, followed by those of our counters.
+    pub(crate) synced_steps: Vec<AtomicUsize>,
+    /// The time at which the `Item` was created.
+    pub(crate) created_at: SystemTime,
+    /// The time at which the `Item` was dropped, if it is kept in the tree to show its outcome.
+    pub(crate) finished_at: Option<Instant>,
+    /// The generation at which the task was last changed, which can be updated without locking the node exclusively.
+    pub(crate) changed_at: AtomicU64,
+    /// Set if the task was asked to cancel, shared with its `Item`.
+    pub(crate) cancel_requested: Arc<AtomicBool>,
+    /// Set if the task was asked to pause, shared with its `Item`.
+    pub(crate) pause: Arc<PauseFlag>,
+}
+
+impl Node {
+    /// Return a copy of our task with the current step and cancellation request filled in, and its state set to paused
+    /// if it was asked to pause.
+    ///
+    /// Tasks with progress of their own don't roll up the progress of their children.
+    pub(crate) fn snapshot(&self) -> Task {
+        let mut task = self.task.clone();
+        task.cancel_requested = self.cancel_requested.load(Ordering::Relaxed);
+        if let Some(progress) = task.progress.as_mut() {
+            progress.step = self.step.get();
+            if self.pause.is_paused() && !matches!(progress.state, State::Finished(_)) {
+                progress.state = State::Paused;
+            }
+            task.roll_up = None;
+        }
+        for (counter, step) in task.counters.iter_mut().zip(&self.counters) {
+            counter.step = step.get();
+        }
+        task
+    }
+
+    /// Pick up changes made through our step counters, and mark the task as changed in `generation` if there were any.
+    pub(crate) fn sync_steps(&self, generation: Generation) {
+        let own_step = self.task.progress.as_ref().map(|_| &self.step);
+        let mut changed = false;
+        for (synced, step) in self
+            .synced_steps
+            .iter()
+            .zip(std::iter::once(own_step).chain(self.counters.iter().map(Some)))
+        {
+            if let Some(step) = step {
+                let step = step.get();
+                changed |= synced.swap(step, Ordering::Relaxed) != step;
+            }
+        }
+        if changed {
+            self.mark_changed(generation);
+        }
+    }
+
+    pub(crate) fn mark_changed(&self, generation: Generation) {
+        self.changed_at.store(generation, Ordering::Relaxed);
+    }
+
+    pub(crate) fn changed_at(&self) -> Generation {
+        self.changed_at.load(Ordering::Relaxed)
+    }
+
+    fn deep_clone(&self) -> Node {
+        Node {
+            task: self.task.clone(),
+            step: self.step.deep_clone(),
+            counters: self.counters.iter().map(StepCounter::deep_clone).collect(),
+            synced_steps: self
+                .synced_steps
+                .iter()
+                .map(|step| AtomicUsize::new(step.load(Ordering::Relaxed)))
+                .collect(),
+            created_at: self.created_at,
+            finished_at: self.finished_at,
+            changed_at: AtomicU64::new(self.changed_at()),
+            cancel_requested: Arc::new(AtomicBool::new(self.cancel_requested.load(Ordering::Relaxed))),
+            pause: Arc::new(PauseFlag::new(self.pause.is_paused(), self.pause.is_cancelled())),
+        }
+    }
 }
 
 impl Drop for Item {
     fn drop(&mut self) {
-        self.tree.remove(&self.key);
+        match self.keep_finished {
+            None => {
+                if let Some((key, _)) = self.tree.remove(&self.key) {
+                    self.changes.record_removal(key);
+                }
+            }
+            Some(keep_finished) => {
+                self.alter_node(|node| {
+                    node.finished_at = Some(Instant::now());
+                    if let Some(progress) = node.task.progress.as_mut() {
+                        if !matches!(progress.state, State::Finished(_)) {
+                            progress.state = State::Finished(if std::thread::panicking() {
+                                Outcome::Failed
+                            } else {
+                                Outcome::Succeeded
+                            });
+                        }
+                    }
+                });
+                match keep_finished {
+                    KeepFinished::For(_) => {
+                        self.finished.lock().push_back((Instant::now(), self.key.clone()));
+                        self.evict_expired_tasks();
+                    }
+                    KeepFinished::UntilParentDrops { max_top_level_tasks } => {
+                        self.evict_finished_children();
+                        self.keep_until_parent_drops(max_top_level_tasks);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Item {
+    /// Remove finished tasks which were kept for longer than configured with [`KeepFinished::For`].
+    pub(crate) fn evict_expired_tasks(&self) {
+        if let Some(KeepFinished::For(duration)) = self.keep_finished {
+            let mut finished = self.finished.lock();
+            while let Some((_, key)) = finished
+                .front()
+                .filter(|(finished_at, _)| finished_at.elapsed() >= duration)
+                .cloned()
+            {
+                finished.pop_front();
+                self.remove_if_finished(&key);
+            }
+        }
+    }
+
+    /// Remove our finished children, which is all there is to remove as they removed their own finished children
+    /// when they were dropped, and let those finishing later remove themselves.
+    fn evict_finished_children(&self) {
+        let keys = {
+            let mut children = self.finished_children.lock();
+            children.parent_dropped = true;
+            std::mem::take(&mut children.keys)
+        };
+        for key in keys {
+            self.remove_if_finished(&key);
+        }
+    }
+
+    /// Keep ourselves until our parent drops, or remove ourselves if it was dropped already. Our parent is the root
+    /// if we are a top-level task, which is never dropped, so only the `max_top_level_tasks` most recent ones are kept.
+    fn keep_until_parent_drops(&self, max_top_level_tasks: usize) {
+        let mut siblings = self.finished_siblings.lock();
+        if siblings.parent_dropped {
+            drop(siblings);
+            self.remove_if_finished(&self.key);
+            return;
+        }
+        siblings.keys.push_back(self.key.clone());
+        if self.key.level() == 1 {
+            while siblings.keys.len() > max_top_level_tasks {
+                if let Some(key) = siblings.keys.pop_front() {
+                    self.remove_if_finished(&key);
+                }
+            }
+        }
+    }
+
+    fn remove_if_finished(&self, key: &Key) {
+        if let Some((key, _)) = self.tree.remove_if(key, |_, node| node.finished_at.is_some()) {
+            self.changes.record_removal(key);
+        }
     }
 }
 
@@ -53,57 +287,84 @@ impl Item {
     ///
     /// **Note** that this method can be called multiple times, changing the bounded-ness and unit at will.
     pub fn init(&mut self, max: Option<Step>, unit: Option<Unit>) {
-        if let Some(mut r) = self.tree.get_mut(&self.key) {
-            r.value_mut().progress = Some(Value {
+        let step = &self.step;
+        self.alter_node(|node| {
+            step.set(0);
+            node.task.progress = Some(Value {
                 done_at: max,
                 unit,
+                created_at: Some(node.created_at),
+                initialized_at: Some(SystemTime::now()),
                 ..Default::default()
             })
-        };
+        });
+    }
+
+    /// Set the maximum to `max`, without altering the current step or state, and return the previous maximum.
+    ///
+    /// Use this, as opposed to [`init(…)`](#method.init), to change the upper bound while progress is being made.
+    /// If `max` is `None`, the progress becomes unbounded.
+    ///
+    /// **Note**: that this call has no effect unless `init(…)` was called before.
+    pub fn set_max(&mut self, max: Option<Step>) -> Option<Step> {
+        let mut previous = None;
+        self.alter_progress(|p| previous = std::mem::replace(&mut p.done_at, max));
+        previous
+    }
+
+    /// Increment the maximum by `step`, without altering the current step or state.
+    ///
+    /// If the progress is unbounded, it becomes bounded with `step` as maximum.
+    ///
+    /// **Note**: that this call has no effect unless `init(…)` was called before.
+    pub fn inc_max(&mut self, step: Step) {
+        self.alter_progress(|p| p.done_at = Some(p.done_at.unwrap_or(0).saturating_add(step)));
     }
 
-    fn alter_progress(&mut self, f: impl FnMut(&mut Value)) {
+    /// Call `f` on our node and mark it as changed.
+    fn alter_node(&self, f: impl FnOnce(&mut Node)) {
         if let Some(mut r) = self.tree.get_mut(&self.key) {
-            // NOTE: since we wrap around, if there are more tasks than we can have IDs for,
-            // and if all these tasks are still alive, two progress trees may see the same ID
-            // when these go out of scope, they delete the key and the other tree will not find
-            // its value anymore. Besides, it's probably weird to see tasks changing their progress
-            // all the time…
-            r.value_mut().progress.as_mut().map(f);
+            let node = r.value_mut();
+            node.mark_changed(self.changes.current());
+            f(node);
         };
     }
 
+    fn alter_progress(&self, f: impl FnMut(&mut Value)) {
+        self.alter_node(|node| {
+            node.task.progress.as_mut().map(f);
+        });
+    }
+
     /// Set the name of this task's progress to the given `name`.
     pub fn set_name(&mut self, name: impl Into<String>) {
-        if let Some(mut r) = self.tree.get_mut(&self.key) {
-            r.value_mut().name = name.into();
-        };
+        self.alter_node(|node| node.task.name = name.into());
     }
 
     /// Get the name of this task's progress
     pub fn name(&self) -> Option<String> {
-        self.tree.get(&self.key).map(|r| r.value().name.to_owned())
+        self.tree.get(&self.key).map(|r| r.value().task.name.to_owned())
     }
 
     /// Returns the current step, as controlled by `inc*(…)` calls
     pub fn step(&self) -> Option<Step> {
         self.tree
             .get(&self.key)
-            .and_then(|r| r.value().progress.as_ref().map(|p| p.step))
+            .and_then(|r| r.value().task.progress.as_ref().map(|_| self.step.get()))
     }
 
     /// Returns the maximum about of items we expect, as provided with the `init(…)` call
     pub fn max(&self) -> Option<Step> {
         self.tree
             .get(&self.key)
-            .and_then(|r| r.value().progress.as_ref().and_then(|p| p.done_at))
+            .and_then(|r| r.value().task.progress.as_ref().and_then(|p| p.done_at))
     }
 
     /// Returns the (cloned) unit associated with this Progress
     pub fn unit(&self) -> Option<Unit> {
         self.tree
             .get(&self.key)
-            .and_then(|r| r.value().progress.as_ref().and_then(|p| p.unit.clone()))
+            .and_then(|r| r.value().task.progress.as_ref().and_then(|p| p.unit.clone()))
     }
 
     /// Set the current progress to the given `step`.
@@ -111,7 +372,7 @@ impl Item {
     /// **Note**: that this call has no effect unless `init(…)` was called before.
     pub fn set(&mut self, step: Step) {
         self.alter_progress(|p| {
-            p.step = step;
+            self.step.set(step);
             p.state = State::Running;
         });
     }
@@ -121,7 +382,7 @@ impl Item {
     /// **Note**: that this call has no effect unless `init(…)` was called before.
     pub fn inc_by(&mut self, step: Step) {
         self.alter_progress(|p| {
-            p.step += step;
+            self.step.inc_by(step);
             p.state = State::Running;
         });
     }
@@ -131,11 +392,57 @@ impl Item {
     /// **Note**: that this call has no effect unless `init(…)` was called before.
     pub fn inc(&mut self) {
         self.alter_progress(|p| {
-            p.step += 1;
+            self.step.inc();
             p.state = State::Running;
         });
     }
 
+    /// Returns a shared counter for the step of this `Item`, which can be used to make progress without locking.
+    ///
+    /// Use it in hot loops and share it across threads to avoid the per-call cost of [`set(…)`](#method.set)
+    /// and [`inc(…)`](#method.inc). The renderers will see its latest value when taking their next snapshot.
+    ///
+    /// **Note**: that changes to the counter have no visible effect unless `init(…)` was called before.
+    pub fn counter(&self) -> StepCounter {
+        self.step.clone()
+    }
+
+    /// Add a counter named `name` to track an additional quantity next to the primary progress, like bytes next to objects,
+    /// and return a shared handle to its step.
+    ///
+    /// As with [`init(…)`](#method.init), `max` is its upper bound if known and `unit` is used for display purposes.
+    /// Renderers show counters after the primary progress of the task, each with its own throughput, once the task
+    /// was [initialized](#method.init).
+    ///
+    /// If a counter with the same `name` exists already, its `max` and `unit` are updated and its handle is returned.
+    pub fn add_counter(&mut self, name: impl Into<String>, max: Option<Step>, unit: Option<Unit>) -> StepCounter {
+        let name = name.into();
+        let mut handle = None;
+        self.alter_node(|node| {
+            handle = Some(match node.task.counters.iter().position(|c| c.name == name) {
+                Some(index) => {
+                    let counter = &mut node.task.counters[index];
+                    counter.done_at = max;
+                    counter.unit = unit;
+                    node.counters[index].clone()
+                }
+                None => {
+                    node.task.counters.push(Counter {
+                        name,
+                        step: 0,
+                        done_at: max,
+                        unit,
+                    });
+                    let step = StepCounter::default();
+                    node.counters.push(step.clone());
+                    node.synced_steps.push(AtomicUsize::new(0));
+                    step
+                }
+            });
+        });
+        handle.unwrap_or_default()
+    }
+
     /// Call to indicate that progress cannot be indicated, and that the task cannot be interrupted.
     /// Use this, as opposed to `halted(…)`, if a non-interruptable call is about to be made without support
     /// for any progress indication.
@@ -143,9 +450,12 @@ impl Item {
     /// If `eta` is `Some(…)`, it specifies the time at which this task is expected to
     /// make progress again.
     ///
+    /// The `reason` can be any string, like `format!("retry {}/{}", attempt, max)`.
+    ///
     /// The blocked-state is undone next time [`tree::Item::set(…)`](./struct.Item.html#method.set) is called.
-    pub fn blocked(&mut self, reason: &'static str, eta: Option<SystemTime>) {
-        self.alter_progress(|p| p.state = State::Blocked(reason, eta));
+    pub fn blocked(&mut self, reason: impl Into<Arc<str>>, eta: Option<SystemTime>) {
+        let reason = reason.into();
+        self.alter_progress(|p| p.state = State::Blocked(reason.clone(), eta));
     }
 
     /// Call to indicate that progress cannot be indicated, even though the task can be interrupted.
@@ -155,31 +465,137 @@ impl Item {
     /// If `eta` is `Some(…)`, it specifies the time at which this task is expected to
     /// make progress again.
     ///
+    /// The `reason` can be any string, like `format!("waiting for lock on {}", path)`.
+    ///
     /// The halted-state is undone next time [`tree::Item::set(…)`](./struct.Item.html#method.set) is called.
-    pub fn halted(&mut self, reason: &'static str, eta: Option<SystemTime>) {
-        self.alter_progress(|p| p.state = State::Halted(reason, eta));
+    pub fn halted(&mut self, reason: impl Into<Arc<str>>, eta: Option<SystemTime>) {
+        let reason = reason.into();
+        self.alter_progress(|p| p.state = State::Halted(reason.clone(), eta));
+    }
+
+    /// Returns true if this task or one of its parents was asked to cancel, typically by a user through a renderer.
+    ///
+    /// Poll this regularly to stop work early, and acknowledge the request by calling
+    /// [`finish(Outcome::Cancelled)`](#method.finish) or by dropping this instance. The cost of this call is negligible.
+    pub fn is_cancel_requested(&self) -> bool {
+        self.cancel_requested.load(Ordering::Relaxed)
+    }
+
+    /// Ask this task and all of its children to pause, which is shown by renderers as [`State::Paused`] and
+    /// lets [`wait_if_paused()`](#method.wait_if_paused) block until they are [resumed](#method.resume).
+    ///
+    /// Children added while paused start out paused.
+    pub fn pause(&self) {
+        self.set_paused(&self.key, true);
+    }
+
+    /// Resume this task and all of its children after they were [paused](#method.pause), waking up everyone
+    /// waiting for them.
+    pub fn resume(&self) {
+        self.set_paused(&self.key, false);
+    }
+
+    /// Returns true if this task or one of its parents was asked to pause. The cost of this call is negligible.
+    pub fn is_paused(&self) -> bool {
+        self.pause.is_paused()
+    }
+
+    /// Block the current thread while this task is paused, or return immediately if it isn't.
+    ///
+    /// Waiting ends early once the task is [asked to cancel](#method.is_cancel_requested), so workers can stop.
+    ///
+    /// Call this regularly from workers, for instance between units of work, to let users throttle them by hand.
+    pub fn wait_if_paused(&self) {
+        self.pause.wait()
+    }
+
+    /// Like [`wait_if_paused()`](#method.wait_if_paused), but return a future which is ready once this task
+    /// isn't paused or was asked to cancel, for use in async workers.
+    pub fn wait_if_paused_async(&self) -> WaitIfPaused {
+        WaitIfPaused {
+            flag: self.pause.clone(),
+        }
+    }
+
+    /// Pause or resume the task at `key` and all of its children, marking those that changed.
+    pub(crate) fn set_paused(&self, key: &Key, paused: bool) {
+        set_paused(&self.tree, &self.changes, key, paused)
+    }
+
+    /// Call to indicate that the task has finished with the given `outcome` and won't make any more progress.
+    ///
+    /// This is most useful in conjunction with [`tree::Options::keep_finished`](./struct.Options.html#structfield.keep_finished),
+    /// which keeps the task visible along with its outcome after this instance was dropped.
+    /// If unset by the time the `Item` is dropped, its outcome will be [`Outcome::Failed`] when dropped during a panic,
+    /// or [`Outcome::Succeeded`] otherwise.
+    ///
+    /// The finished-state is undone next time [`tree::Item::set(…)`](./struct.Item.html#method.set) is called.
+    pub fn finish(&mut self, outcome: Outcome) {
+        self.alter_progress(|p| p.state = State::Finished(outcome));
+    }
+
+    /// Opt into computing the progress of this `Item` from the progress of its children, as specified by `roll_up`,
+    /// or opt out with `None`.
+    ///
+    /// This only has an effect if this `Item` serves as organizational unit, i.e. if `init(…)` was never called.
+    /// See [`progress::roll_up()`](../progress/fn.roll_up.html) for details.
+    pub fn set_roll_up(&mut self, roll_up: Option<RollUp>) {
+        self.alter_node(|node| node.task.roll_up = roll_up);
+    }
+
+    /// Set the `weight` of this `Item` relative to its siblings, for use when its parent
+    /// [rolls up](#method.set_roll_up) their progress using [`RollUp::Fraction`]. It defaults to `1.0`.
+    pub fn set_weight(&mut self, weight: f32) {
+        self.alter_node(|node| node.task.weight = Some(weight));
     }
 
     /// Adds a new child `Tree`, whose parent is this instance, with the given `name`.
     ///
-    /// **Important**: The depth of the hierarchy is limited to [`tree::Key::max_level`](./struct.Key.html#method.max_level).
-    /// Exceeding the level will be ignored, and new tasks will be added to this instance's
-    /// level instead.
+    /// Children can be nested arbitrarily deep.
+    ///
+    /// Child ids wrap around eventually, but ids of children that are still alive are skipped. This way two
+    /// live `Item`s never share a key, and dropping one never removes the state of another.
     pub fn add_child(&mut self, name: impl Into<String>) -> Item {
-        let child_key = self.key.add_child(self.highest_child_id);
-        self.tree.insert(
-            child_key,
-            Task {
-                name: name.into(),
-                progress: None,
-            },
-        );
-        self.highest_child_id = self.highest_child_id.wrapping_add(1);
+        let name = name.into();
+        let step = StepCounter::default();
+        let is_cancel_requested = self.is_cancel_requested();
+        let cancel_requested = Arc::new(AtomicBool::new(is_cancel_requested));
+        let pause = Arc::new(PauseFlag::new(self.is_paused(), is_cancel_requested));
+        let child_key = loop {
+            let child_key = self.key.add_child(self.highest_child_id);
+            self.highest_child_id = self.highest_child_id.wrapping_add(1);
+            if let Entry::Vacant(entry) = self.tree.entry(child_key.clone()) {
+                entry.insert(Node {
+                    task: Task {
+                        name,
+                        ..Default::default()
+                    },
+                    step: step.clone(),
+                    counters: Vec::new(),
+                    synced_steps: vec![AtomicUsize::new(0)],
+                    created_at: SystemTime::now(),
+                    finished_at: None,
+                    changed_at: AtomicU64::new(self.changes.current()),
+                    cancel_requested: cancel_requested.clone(),
+                    pause: pause.clone(),
+                });
+                break child_key;
+            }
+        };
         Item {
             highest_child_id: 0,
             key: child_key,
             tree: self.tree.clone(),
             messages: self.messages.clone(),
+            message_sink: self.message_sink.clone(),
+            step,
+            keep_finished: self.keep_finished,
+            changes: self.changes.clone(),
+            cancel_requested,
+            pause,
+            finished_children: Default::default(),
+            finished_siblings: self.finished_children.clone(),
+            finished: self.finished.clone(),
         }
     }
 
@@ -188,22 +604,59 @@ impl Item {
     /// Use this to provide additional,human-readable information about the progress
     /// made, including indicating success or failure.
     pub fn message(&mut self, level: MessageLevel, message: impl Into<String>) {
-        let message: String = message.into();
-        self.messages.lock().push_overwrite(
-            level,
-            {
-                let name = self.tree.get(&self.key).map(|v| v.name.to_owned()).unwrap_or_default();
+        self.message_with_fields(level, message, Vec::new())
+    }
 
-                #[cfg(feature = "progress-tree-log")]
-                match level {
-                    MessageLevel::Failure => crate::warn!("{} → {}", name, message),
-                    MessageLevel::Info | MessageLevel::Success => crate::info!("{} → {}", name, message),
-                };
+    /// Create a `message` of the given `level` with typed key/value `fields` and store it with the progress tree.
+    ///
+    /// Use this to attach machine-readable details like paths or error codes to a message.
+    /// If the `progress-tree-log` feature is enabled, `fields` are passed as key-values to the `log` crate.
+    /// If a [`MessageSink`](crate::tree::MessageSink) is configured, the message is written to its file as well.
+    pub fn message_with_fields(&mut self, level: MessageLevel, message: impl Into<String>, fields: Vec<Field>) {
+        let message = self.new_message(level, message.into(), fields);
+        log_message(&message);
+        self.push_message(message);
+    }
 
-                name
-            },
+    /// Create a message of the given `level` originating from this task.
+    pub(crate) fn new_message(&self, level: MessageLevel, message: String, fields: Vec<Field>) -> Message {
+        let path = self.name_path();
+        Message {
+            time: SystemTime::now(),
+            level,
+            origin: path.last().cloned().unwrap_or_default(),
+            key: Some(self.key.clone()),
+            path,
             message,
-        )
+            fields,
+        }
+    }
+
+    /// Store `message` in the message buffer and the message sink, if configured, without forwarding it to `log`.
+    ///
+    /// The message is written to the sink first so that writing to its file never blocks the message buffer.
+    pub(crate) fn push_message(&self, message: Message) {
+        if let Some(sink) = self.message_sink.as_ref() {
+            sink.lock().write(&message);
+        }
+        self.messages.lock().push_overwrite_message(message);
+    }
+
+    /// The names of all tasks from the top-level down to ourselves.
+    fn name_path(&self) -> Vec<String> {
+        let mut path = Vec::with_capacity(self.key.level());
+        let mut key = Some(self.key.clone());
+        while let Some(current) = key {
+            path.push(
+                self.tree
+                    .get(&current)
+                    .map(|v| v.task.name.to_owned())
+                    .unwrap_or_default(),
+            );
+            key = current.parent().filter(|parent| parent.level() > 0);
+        }
+        path.reverse();
+        path
     }
 
     /// Create a message indicating the task is done
@@ -221,12 +674,45 @@ impl Item {
         self.message(MessageLevel::Info, message)
     }
 
+    /// Create a message indicating a recoverable problem which doesn't cause the task to fail
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.message(MessageLevel::Warning, message)
+    }
+
+    /// Create a message with details that are typically only of interest when investigating issues
+    pub fn debug(&mut self, message: impl Into<String>) {
+        self.message(MessageLevel::Debug, message)
+    }
+
     pub(crate) fn deep_clone(&self) -> Item {
+        let tree: DashMap<_, _> = self
+            .tree
+            .iter()
+            .map(|r| (r.key().clone(), r.value().deep_clone()))
+            .collect();
         Item {
-            key: self.key,
+            key: self.key.clone(),
             highest_child_id: self.highest_child_id,
-            tree: Arc::new(self.tree.deref().clone()),
+            step: tree
+                .get(&self.key)
+                .map(|r| r.value().step.clone())
+                .unwrap_or_else(|| self.step.deep_clone()),
+            cancel_requested: tree
+                .get(&self.key)
+                .map(|r| r.value().cancel_requested.clone())
+                .unwrap_or_else(|| Arc::new(AtomicBool::new(self.is_cancel_requested()))),
+            pause: tree
+                .get(&self.key)
+                .map(|r| r.value().pause.clone())
+                .unwrap_or_else(|| Arc::new(PauseFlag::new(self.is_paused(), self.is_cancel_requested()))),
+            tree: Arc::new(tree),
             messages: Arc::new(Mutex::new(self.messages.lock().clone())),
+            message_sink: self.message_sink.clone(),
+            keep_finished: self.keep_finished,
+            changes: Arc::new(self.changes.deep_clone()),
+            finished_children: Arc::new(Mutex::new(self.finished_children.lock().clone())),
+            finished_siblings: Default::default(),
+            finished: Arc::new(Mutex::new(self.finished.lock().clone())),
         }
     }
 }
@@ -254,6 +740,26 @@ impl crate::Progress for Item {
         Item::max(self)
     }
 
+    fn set_max(&mut self, max: Option<Step>) -> Option<Step> {
+        Item::set_max(self, max)
+    }
+
+    fn inc_max(&mut self, step: Step) {
+        Item::inc_max(self, step)
+    }
+
+    fn is_cancel_requested(&self) -> bool {
+        Item::is_cancel_requested(self)
+    }
+
+    fn is_paused(&self) -> bool {
+        Item::is_paused(self)
+    }
+
+    fn wait_if_paused(&self) {
+        Item::wait_if_paused(self)
+    }
+
     fn step(&self) -> usize {
         Item::step(self).unwrap_or(0)
     }
@@ -273,4 +779,8 @@ impl crate::Progress for Item {
     fn message(&mut self, level: MessageLevel, message: impl Into<String>) {
         Item::message(self, level, message)
     }
+
+    fn message_with_fields(&mut self, level: MessageLevel, message: impl Into<String>, fields: Vec<Field>) {
+        Item::message_with_fields(self, level, message, fields)
+    }
 }
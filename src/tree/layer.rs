@@ -0,0 +1,213 @@
+#[cfg(feature = "progress-tree-log")]
+use crate::tree::item::LOG_TARGET;
+use crate::{
+    messages::{Field, MessageLevel},
+    progress::Step,
+    tree::{Item, Root},
+};
+use parking_lot::Mutex;
+use std::{fmt, sync::Arc};
+use tracing_core::{
+    field::{self, Visit},
+    span, Event, Level, Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan};
+
+/// A [`Layer`](tracing_subscriber::Layer) which turns spans into [`Item`]s of a progress tree and events into messages,
+/// so renderers show live work without a second set of instrumentation.
+///
+/// * Spans become children of the `Item` of their closest parent span, or of the tree itself, named after the span,
+///   and are removed once the span closes.
+/// * The `total` and `step` fields of a span, whether set on creation or recorded later,
+///   initialize the `Item` with an upper bound and set its progress respectively.
+/// * Events become messages of the `Item` of their span, with the `message` field as text and all other fields as
+///   [`Field`]s. `ERROR` maps to [`MessageLevel::Failure`], `WARN` to `Warning`, `INFO` to `Info`, and all others to `Debug`.
+///   Messages of the tree which come back as events, for instance through `tracing_log::LogTracer`, are ignored.
+/// ```rust
+/// use tracing_subscriber::layer::SubscriberExt;
+/// let tree = prodash::Tree::new();
+/// let subscriber = tracing_subscriber::registry().with(prodash::tree::layer::ProgressLayer::new(tree.clone()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ProgressLayer {
+    root: Root,
+}
+
+impl ProgressLayer {
+    /// Create a new instance adding all spans and events to `root`.
+    pub fn new(root: Root) -> Self {
+        ProgressLayer { root }
+    }
+}
+
+/// A [`SpanItem`] as stored in the extensions of its span.
+///
+/// It is shared so that it can be used after releasing the extensions, as messages may be logged and come back as events.
+type SharedSpanItem = Arc<Mutex<SpanItem>>;
+
+/// The `Item` of a span.
+struct SpanItem {
+    item: Item,
+    initialized: bool,
+}
+
+impl SpanItem {
+    fn apply(&mut self, progress: SpanProgress) {
+        if let Some(total) = progress.total {
+            if self.initialized {
+                self.item.set_max(Some(total));
+            } else {
+                self.item.init(Some(total), None);
+                self.initialized = true;
+            }
+        }
+        if let Some(step) = progress.step {
+            if !self.initialized {
+                self.item.init(None, None);
+                self.initialized = true;
+            }
+            self.item.set(step);
+        }
+    }
+}
+
+/// The fields of a span which drive the progress of its `Item`.
+#[derive(Default)]
+struct SpanProgress {
+    total: Option<Step>,
+    step: Option<Step>,
+}
+
+impl Visit for SpanProgress {
+    fn record_i64(&mut self, field: &field::Field, value: i64) {
+        if value >= 0 {
+            self.record_u64(field, value as u64);
+        }
+    }
+
+    fn record_u64(&mut self, field: &field::Field, value: u64) {
+        match field.name() {
+            "total" => self.total = Some(value as Step),
+            "step" => self.step = Some(value as Step),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, _field: &field::Field, _value: &dyn fmt::Debug) {}
+}
+
+/// The text and fields of an event.
+#[derive(Default)]
+struct EventFields {
+    message: String,
+    fields: Vec<Field>,
+    /// The target of the `log` record this event was converted from, if any.
+    log_target: Option<String>,
+}
+
+impl Visit for EventFields {
+    fn record_f64(&mut self, field: &field::Field, value: f64) {
+        self.fields.push(Field::new(field.name(), value));
+    }
+
+    fn record_i64(&mut self, field: &field::Field, value: i64) {
+        self.fields.push(Field::new(field.name(), value));
+    }
+
+    fn record_u64(&mut self, field: &field::Field, value: u64) {
+        self.fields.push(Field::new(field.name(), value));
+    }
+
+    fn record_bool(&mut self, field: &field::Field, value: bool) {
+        self.fields.push(Field::new(field.name(), value));
+    }
+
+    fn record_str(&mut self, field: &field::Field, value: &str) {
+        match field.name() {
+            "message" => self.message = value.to_owned(),
+            "log.target" => self.log_target = Some(value.to_owned()),
+            name => self.fields.push(Field::new(name, value)),
+        }
+    }
+
+    fn record_debug(&mut self, field: &field::Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "message" => self.message = format!("{:?}", value),
+            name => self.fields.push(Field::new(name, format!("{:?}", value))),
+        }
+    }
+}
+
+fn message_level(level: &Level) -> MessageLevel {
+    match *level {
+        Level::ERROR => MessageLevel::Failure,
+        Level::WARN => MessageLevel::Warning,
+        Level::INFO => MessageLevel::Info,
+        _ => MessageLevel::Debug,
+    }
+}
+
+impl<S> tracing_subscriber::Layer<S> for ProgressLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let span = match ctx.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+        let name = attrs.metadata().name();
+        let item = span
+            .scope()
+            .skip(1)
+            .find_map(|parent| parent.extensions().get::<SharedSpanItem>().cloned())
+            .map(|parent| parent.lock().item.add_child(name))
+            .unwrap_or_else(|| self.root.add_child(name));
+        let mut span_item = SpanItem {
+            item,
+            initialized: false,
+        };
+        let mut progress = SpanProgress::default();
+        attrs.record(&mut progress);
+        span_item.apply(progress);
+        span.extensions_mut()
+            .insert::<SharedSpanItem>(Arc::new(Mutex::new(span_item)));
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut progress = SpanProgress::default();
+            values.record(&mut progress);
+            let span_item = span.extensions().get::<SharedSpanItem>().cloned();
+            if let Some(span_item) = span_item {
+                span_item.lock().apply(progress);
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let level = message_level(event.metadata().level());
+        let mut fields = EventFields::default();
+        event.record(&mut fields);
+        #[cfg(feature = "progress-tree-log")]
+        if event.metadata().target() == LOG_TARGET || fields.log_target.as_deref() == Some(LOG_TARGET) {
+            return;
+        }
+        let EventFields { message, fields, .. } = fields;
+        let span_item = ctx.event_scope(event).and_then(|scope| {
+            scope
+                .into_iter()
+                .find_map(|span| span.extensions().get::<SharedSpanItem>().cloned())
+        });
+        match span_item {
+            Some(span_item) => span_item.lock().item.message_with_fields(level, message, fields),
+            None => self.root.message_with_fields(level, message, fields),
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id) {
+            span.extensions_mut().remove::<SharedSpanItem>();
+        }
+    }
+}
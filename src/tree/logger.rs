@@ -0,0 +1,131 @@
+use crate::{
+    messages::{Field, FieldValue, Float, Message, MessageLevel},
+    tree::{item::LOG_TARGET, Root},
+};
+use log::{kv, Level, LevelFilter, Log, Metadata, Record};
+use std::time::SystemTime;
+
+/// A [`Log`] implementation which turns log records into messages of a progress tree, so they show up in the message
+/// pane of the TUI and above the progress of the line renderer instead of interfering with them.
+///
+/// Records become messages with their target as origin and their key-values as [`Field`]s. `Error` maps to
+/// [`MessageLevel::Failure`], `Warn` to `Warning`, `Info` to `Info`, and `Debug` as well as `Trace` to `Debug`.
+///
+/// Records the tree itself emits through the `progress-tree-log` feature are ignored, as their messages are already
+/// in the tree.
+/// ```rust
+/// let tree = prodash::Tree::new();
+/// prodash::tree::Logger::new(tree.clone())
+///     .level(log::LevelFilter::Debug)
+///     .target_level("hyper", log::LevelFilter::Warn)
+///     .install()
+///     .expect("no other logger was installed");
+/// log::info!("shows up in the tree");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Logger {
+    root: Root,
+    level: LevelFilter,
+    target_levels: Vec<(String, LevelFilter)>,
+}
+
+impl Logger {
+    /// Create a new instance adding records of level `Info` and above to `root`.
+    pub fn new(root: Root) -> Self {
+        Logger {
+            root,
+            level: LevelFilter::Info,
+            target_levels: Vec::new(),
+        }
+    }
+
+    /// Set the maximum `level` of records to keep, unless their target is configured with
+    /// [`target_level(…)`](#method.target_level).
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Set the maximum `level` of records whose target starts with `prefix`, like `hyper` or `my_crate::io`.
+    ///
+    /// Use [`LevelFilter::Off`] to ignore a target entirely. If multiple prefixes match, the longest one wins.
+    pub fn target_level(mut self, prefix: impl Into<String>, level: LevelFilter) -> Self {
+        self.target_levels.push((prefix.into(), level));
+        self
+    }
+
+    /// Install this instance as global logger and set the maximum level of `log` accordingly.
+    ///
+    /// This fails if a logger was installed already.
+    pub fn install(self) -> Result<(), log::SetLoggerError> {
+        let max_level = self
+            .target_levels
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.level, std::cmp::max);
+        log::set_logger(Box::leak(Box::new(self)))?;
+        log::set_max_level(max_level);
+        Ok(())
+    }
+
+    fn level_for(&self, target: &str) -> LevelFilter {
+        self.target_levels
+            .iter()
+            .filter(|(prefix, _)| target.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.level)
+    }
+}
+
+/// Collects the key-values of a record.
+struct Fields(Vec<Field>);
+
+impl<'kvs> kv::VisitSource<'kvs> for Fields {
+    fn visit_pair(&mut self, key: kv::Key<'kvs>, value: kv::Value<'kvs>) -> Result<(), kv::Error> {
+        let value = if let Some(v) = value.to_bool() {
+            FieldValue::Bool(v)
+        } else if let Some(v) = value.to_u64() {
+            FieldValue::Uint(v)
+        } else if let Some(v) = value.to_i64() {
+            FieldValue::Int(v)
+        } else if let Some(v) = value.to_f64() {
+            FieldValue::Float(Float(v))
+        } else {
+            FieldValue::String(value.to_string())
+        };
+        self.0.push(Field::new(key.as_str(), value));
+        Ok(())
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target() != LOG_TARGET && metadata.level() <= self.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let mut fields = Fields(Vec::new());
+        record.key_values().visit(&mut fields).ok();
+        let message = Message {
+            time: SystemTime::now(),
+            level: match record.level() {
+                Level::Error => MessageLevel::Failure,
+                Level::Warn => MessageLevel::Warning,
+                Level::Info => MessageLevel::Info,
+                Level::Debug | Level::Trace => MessageLevel::Debug,
+            },
+            origin: record.target().to_owned(),
+            key: None,
+            path: Vec::new(),
+            message: record.args().to_string(),
+            fields: fields.0,
+        };
+        self.root.push_message(message);
+    }
+
+    fn flush(&self) {}
+}
@@ -2,7 +2,34 @@
 mod tests;
 
 mod root;
-pub use root::{Options, Root};
+pub use root::{KeepFinished, Options, Root};
 
 mod item;
 pub use item::Item;
+
+mod counter;
+pub use counter::StepCounter;
+
+mod sink;
+pub(crate) use sink::{read_history, SinkWriter};
+pub use sink::{MessageFormat, MessageSink};
+
+mod pause;
+pub(crate) use pause::PauseFlag;
+pub use pause::WaitIfPaused;
+
+#[cfg(feature = "rayon")]
+/// Progress reporting for rayon's parallel iterators.
+pub mod par_iter;
+
+#[cfg(feature = "tracing")]
+/// A `tracing` layer mapping spans into the progress tree.
+pub mod layer;
+
+#[cfg(feature = "progress-tree-log")]
+mod logger;
+#[cfg(feature = "progress-tree-log")]
+pub use logger::Logger;
+
+mod changes;
+pub(crate) use changes::ChangeTracker;
@@ -0,0 +1,135 @@
+use crate::tree::{Item, StepCounter};
+use rayon::iter::{
+    plumbing::{Consumer, ProducerCallback, UnindexedConsumer},
+    IndexedParallelIterator, Map, ParallelIterator,
+};
+use std::sync::Arc;
+
+/// The counters shared by all worker threads, along with the per-thread `Item`s which are kept until iteration ends.
+#[derive(Debug)]
+struct Shared {
+    step: StepCounter,
+    per_thread: Vec<StepCounter>,
+    _threads: Vec<Item>,
+}
+
+impl Shared {
+    fn record(&self) {
+        self.step.inc();
+        if let Some(step) = rayon::current_thread_index().and_then(|index| self.per_thread.get(index)) {
+            step.inc();
+        }
+    }
+}
+
+/// Wrap parallel iterators to report their progress to a single [`Item`], which is shared by all worker threads
+/// without locking.
+pub trait ParallelIteratorExt: ParallelIterator {
+    /// Increment the step of `progress` with each item processed by any worker thread.
+    ///
+    /// `progress` is initialized with the amount of items as upper bound if this iterator knows it,
+    /// or as unbounded otherwise.
+    /// ```rust
+    /// use prodash::tree::par_iter::ParallelIteratorExt;
+    /// use rayon::prelude::*;
+    /// let tree = prodash::Tree::new();
+    /// let mut progress = tree.add_child("squaring");
+    /// let sum: u64 = (0..1000u64).into_par_iter().with_progress(&mut progress).map(|n| n * n).sum();
+    /// # assert_eq!(sum, 332_833_500);
+    /// ```
+    fn with_progress(self, progress: &mut Item) -> ParIter<'_, Self> {
+        progress.init(self.opt_len(), progress.unit());
+        ParIter {
+            step: progress.counter(),
+            per_thread_of: None,
+            base: self,
+        }
+    }
+
+    /// Like [`with_progress()`](ParallelIteratorExt::with_progress()), but also add a child to `progress` for each thread
+    /// of the thread pool running the iteration, which counts the items processed by that thread to show how the work is spread.
+    ///
+    /// The children are added once iteration starts, and removed once it ends.
+    fn with_progress_per_thread(self, progress: &mut Item) -> ParIter<'_, Self> {
+        progress.init(self.opt_len(), progress.unit());
+        ParIter {
+            step: progress.counter(),
+            per_thread_of: Some(progress),
+            base: self,
+        }
+    }
+}
+
+impl<I: ParallelIterator> ParallelIteratorExt for I {}
+
+/// A parallel iterator reporting the items it processes to an [`Item`], as created by
+/// [`ParallelIteratorExt::with_progress()`].
+#[derive(Debug)]
+pub struct ParIter<'a, I> {
+    base: I,
+    step: StepCounter,
+    /// The `Item` to add a child to for each thread, which is only known once iteration starts within its thread pool.
+    per_thread_of: Option<&'a mut Item>,
+}
+
+impl<'a, I: ParallelIterator> ParIter<'a, I> {
+    /// Return our base iterator, recording each of its items.
+    fn counted(self) -> Map<I, impl Fn(I::Item) -> I::Item + Sync + Send + 'a> {
+        let threads: Vec<_> = self
+            .per_thread_of
+            .map(|progress| {
+                (0..rayon::current_num_threads())
+                    .map(|index| {
+                        let mut thread = progress.add_child(format!("thread {}", index + 1));
+                        thread.init(None, progress.unit());
+                        thread
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let shared = Arc::new(Shared {
+            step: self.step,
+            per_thread: threads.iter().map(Item::counter).collect(),
+            _threads: threads,
+        });
+        self.base.map(move |item| {
+            shared.record();
+            item
+        })
+    }
+}
+
+impl<I: ParallelIterator> ParallelIterator for ParIter<'_, I> {
+    type Item = I::Item;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.counted().drive_unindexed(consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        self.base.opt_len()
+    }
+}
+
+impl<I: IndexedParallelIterator> IndexedParallelIterator for ParIter<'_, I> {
+    fn drive<C>(self, consumer: C) -> C::Result
+    where
+        C: Consumer<Self::Item>,
+    {
+        self.counted().drive(consumer)
+    }
+
+    fn len(&self) -> usize {
+        self.base.len()
+    }
+
+    fn with_producer<CB>(self, callback: CB) -> CB::Output
+    where
+        CB: ProducerCallback<Self::Item>,
+    {
+        self.counted().with_producer(callback)
+    }
+}
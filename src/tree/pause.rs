@@ -0,0 +1,123 @@
+use parking_lot::{Condvar, Mutex};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll, Waker},
+};
+
+#[derive(Debug, Default)]
+struct Inner {
+    paused: bool,
+    /// Set once the task was asked to cancel, which makes waiting return right away so it can observe the request.
+    cancelled: bool,
+    /// The tasks of futures waiting for us to be resumed.
+    wakers: Vec<Waker>,
+}
+
+/// Whether a task was asked to pause, shared between its `Item` and its node in the tree so workers can wait
+/// until it is resumed.
+#[derive(Debug, Default)]
+pub(crate) struct PauseFlag {
+    /// A copy of `inner.paused` to check it without locking.
+    paused: AtomicBool,
+    inner: Mutex<Inner>,
+    resumed: Condvar,
+}
+
+impl PauseFlag {
+    pub(crate) fn new(paused: bool, cancelled: bool) -> Self {
+        PauseFlag {
+            paused: AtomicBool::new(paused),
+            inner: Mutex::new(Inner {
+                paused,
+                cancelled,
+                wakers: Vec::new(),
+            }),
+            resumed: Condvar::new(),
+        }
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.inner.lock().cancelled
+    }
+
+    /// Pause or resume, waking up everyone waiting if resumed. Returns true if this changed the flag.
+    pub(crate) fn set(&self, paused: bool) -> bool {
+        let wakers = {
+            let mut inner = self.inner.lock();
+            if inner.paused == paused {
+                return false;
+            }
+            inner.paused = paused;
+            self.paused.store(paused, Ordering::Relaxed);
+            std::mem::take(&mut inner.wakers)
+        };
+        if !paused {
+            self.wake_all(wakers);
+        }
+        true
+    }
+
+    /// Stop waiting for good as the task was asked to cancel, waking up everyone waiting. The paused state is kept.
+    pub(crate) fn cancel(&self) {
+        let wakers = {
+            let mut inner = self.inner.lock();
+            if inner.cancelled {
+                return;
+            }
+            inner.cancelled = true;
+            std::mem::take(&mut inner.wakers)
+        };
+        self.wake_all(wakers);
+    }
+
+    fn wake_all(&self, wakers: Vec<Waker>) {
+        self.resumed.notify_all();
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Block the current thread until we are resumed or cancelled, or return immediately if we aren't paused.
+    pub(crate) fn wait(&self) {
+        let mut inner = self.inner.lock();
+        while inner.paused && !inner.cancelled {
+            self.resumed.wait(&mut inner);
+        }
+    }
+
+    fn poll_wait(&self, cx: &mut Context<'_>) -> Poll<()> {
+        let mut inner = self.inner.lock();
+        if !inner.paused || inner.cancelled {
+            return Poll::Ready(());
+        }
+        if !inner.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            inner.wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+/// A future which is ready once its task isn't paused anymore or was asked to cancel, as returned by
+/// [`Item::wait_if_paused_async()`](./struct.Item.html#method.wait_if_paused_async).
+#[derive(Debug)]
+#[must_use = "futures do nothing unless awaited"]
+pub struct WaitIfPaused {
+    pub(crate) flag: Arc<PauseFlag>,
+}
+
+impl Future for WaitIfPaused {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.flag.poll_wait(cx)
+    }
+}
@@ -1,11 +1,17 @@
 use crate::{
     messages::{Message, MessageCopyState, MessageRingBuffer},
-    progress::{Key, Task},
-    tree::Item,
+    progress::{Changes, Generation, Key, Task},
+    tree::{
+        item::{set_paused, Node},
+        read_history, ChangeTracker, Item, MessageSink, SinkWriter, StepCounter,
+    },
 };
 use dashmap::DashMap;
 use parking_lot::Mutex;
-use std::sync::Arc;
+use std::{
+    sync::{atomic::Ordering, Arc},
+    time::Duration,
+};
 
 /// The top-level of the progress tree.
 #[derive(Clone, Debug)]
@@ -49,10 +55,51 @@ impl Root {
 
     /// Copy the entire progress tree into the given `out` vector, so that
     /// it can be traversed from beginning to end in order of hierarchy.
+    ///
+    /// Finished tasks which were kept for longer than configured in [`Options::keep_finished`] are removed along the way.
     pub fn sorted_snapshot(&self, out: &mut Vec<(Key, Task)>) {
         out.clear();
-        out.extend(self.inner.lock().tree.iter().map(|r| (*r.key(), r.value().clone())));
-        out.sort_by_key(|t| t.0);
+        let inner = self.inner.lock();
+        inner.evict_expired_tasks();
+        out.extend(inner.tree.iter().map(|r| (r.key().clone(), r.value().snapshot())));
+        out.sort_by(|a, b| a.0.cmp(&b.0));
+    }
+
+    /// Copy all tasks that were added or changed since the given `generation` into `out`, along with the keys of all
+    /// removed tasks, or copy all tasks if `generation` is `None` or too old.
+    ///
+    /// Use [`Changes::apply_to(…)`](../progress/struct.Changes.html#method.apply_to) to keep a previous snapshot up to date,
+    /// and pass [`Changes::generation`](../progress/struct.Changes.html#structfield.generation) on the next call.
+    /// This is a lot cheaper than obtaining a [`sorted_snapshot(…)`](#method.sorted_snapshot) for large trees with few changes.
+    pub fn changes_since(&self, generation: Option<Generation>, out: &mut Changes) {
+        out.updated.clear();
+        out.removed.clear();
+        let (tree, changes) = {
+            let inner = self.inner.lock();
+            inner.evict_expired_tasks();
+            (inner.tree.clone(), inner.changes.clone())
+        };
+        // Removals are obtained first so that tasks removed in the meantime are reported next time at the latest.
+        out.is_complete = !generation
+            .map(|generation| changes.removals_since(generation, &mut out.removed))
+            .unwrap_or(false);
+        if out.is_complete {
+            out.removed.clear();
+        }
+        let current = changes.advance();
+        // Steps are synced through atomics, so nodes are only locked for reading and writers aren't blocked.
+        for r in tree.iter() {
+            let node = r.value();
+            node.sync_steps(current);
+            if out.is_complete || generation.map(|g| node.changed_at() >= g).unwrap_or(true) {
+                out.updated.push((r.key().clone(), node.snapshot()));
+            }
+        }
+        out.updated.sort_by(|a, b| a.0.cmp(&b.0));
+        out.removed.sort();
+        // Changes made while we were iterating might have been stamped with the current generation,
+        // so include it next time.
+        out.generation = current;
     }
 
     /// Copy all messages from the internal ring buffer into the given `out`
@@ -67,6 +114,92 @@ impl Root {
         self.inner.lock().messages.lock().copy_new(out, prev)
     }
 
+    /// Copy up to `max` of the most recent messages into the given `out` vector, ordered from oldest to newest.
+    ///
+    /// If a [`MessageSink`] is configured, messages are read from its files, which allows to go back further than the
+    /// [capacity](#method.messages_capacity) of the in-memory buffer. Otherwise, or if the files can't be read, the messages
+    /// are copied from the in-memory buffer.
+    pub fn copy_message_history(&self, out: &mut Vec<Message>, max: usize) {
+        let (sink, messages) = self.message_stores();
+        if let Some(sink) = sink {
+            // Files are read without holding any lock, so they may be rotated in the meantime, which is rare enough.
+            let paths = sink.lock().history_paths();
+            if read_history(&paths, out, max).is_ok() {
+                return;
+            }
+        }
+        messages.lock().copy_all(out);
+        out.drain(..out.len().saturating_sub(max));
+    }
+
+    /// Store `message` in the message buffer and the message sink, if configured, without forwarding it to `log`.
+    ///
+    /// Unlike [`Item::push_message()`], this doesn't hold our lock while writing to the sink.
+    #[cfg(any(feature = "progress-tree-log", feature = "tracing"))]
+    pub(crate) fn push_message(&self, message: Message) {
+        let (sink, messages) = self.message_stores();
+        if let Some(sink) = sink {
+            sink.lock().write(&message);
+        }
+        messages.lock().push_overwrite_message(message);
+    }
+
+    /// Create a message of the given `level` which isn't associated with any task, like
+    /// [`Item::message_with_fields()`], but without holding our lock while logging it or writing to the sink.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn message_with_fields(
+        &self,
+        level: crate::messages::MessageLevel,
+        message: String,
+        fields: Vec<crate::messages::Field>,
+    ) {
+        let message = self.inner.lock().new_message(level, message, fields);
+        crate::tree::item::log_message(&message);
+        self.push_message(message);
+    }
+
+    /// Return the message sink and the message buffer, to use them without holding our lock.
+    fn message_stores(&self) -> (Option<Arc<Mutex<SinkWriter>>>, Arc<Mutex<MessageRingBuffer>>) {
+        let inner = self.inner.lock();
+        (inner.message_sink.clone(), inner.messages.clone())
+    }
+
+    /// Ask the task at `key` and all of its children to cancel, which they can observe with
+    /// [`Item::is_cancel_requested()`](./struct.Item.html#method.is_cancel_requested).
+    ///
+    /// Cancellation is cooperative, so tasks keep running until they acknowledge the request by finishing.
+    /// Tasks waiting while [paused](#method.request_pause) are woken up to observe it.
+    pub fn request_cancel(&self, key: &Key) {
+        let (tree, changes) = self.tree_and_changes();
+        let generation = changes.current();
+        for r in tree.iter() {
+            if key.contains(r.key()) {
+                let node = r.value();
+                node.cancel_requested.store(true, Ordering::Relaxed);
+                node.pause.cancel();
+                node.mark_changed(generation);
+            }
+        }
+    }
+
+    /// Ask the task at `key` and all of its children to pause, see [`Item::pause()`](./struct.Item.html#method.pause).
+    pub fn request_pause(&self, key: &Key) {
+        let (tree, changes) = self.tree_and_changes();
+        set_paused(&tree, &changes, key, true);
+    }
+
+    /// Resume the task at `key` and all of its children, see [`Item::resume()`](./struct.Item.html#method.resume).
+    pub fn request_resume(&self, key: &Key) {
+        let (tree, changes) = self.tree_and_changes();
+        set_paused(&tree, &changes, key, false);
+    }
+
+    /// Return the tree and its change tracker, to use them without holding our lock.
+    fn tree_and_changes(&self) -> (Arc<DashMap<Key, Node>>, Arc<ChangeTracker>) {
+        let inner = self.inner.lock();
+        (inner.tree.clone(), inner.changes.clone())
+    }
+
     /// Duplicate all content and return it.
     ///
     /// This is an expensive operation, whereas `clone()` is not as it is shallow.
@@ -89,6 +222,27 @@ pub struct Options {
     pub initial_capacity: usize,
     /// The amount of messages we can hold before we start overwriting old ones
     pub message_buffer_capacity: usize,
+    /// If set, tasks remain visible in the tree with their [outcome](crate::progress::Outcome) after
+    /// their [`Item`] was dropped, for as long as configured. Otherwise they are removed right away.
+    pub keep_finished: Option<KeepFinished>,
+    /// If set, all messages are also written to a file as they are pushed, which can be read back with
+    /// [`Root::copy_message_history()`].
+    pub message_sink: Option<MessageSink>,
+}
+
+/// Determines for how long finished tasks remain visible after their [`Item`] was dropped.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum KeepFinished {
+    /// Keep finished tasks for the given amount of time.
+    ///
+    /// Expired tasks are removed whenever an `Item` is dropped or a snapshot is taken.
+    For(Duration),
+    /// Keep finished tasks until their parent `Item` is dropped, or remove them right away if it was dropped already.
+    UntilParentDrops {
+        /// The amount of most recently finished tasks directly underneath the [`Root`] to keep, as they have no parent
+        /// to wait for.
+        max_top_level_tasks: usize,
+    },
 }
 
 impl Options {
@@ -104,6 +258,8 @@ impl Default for Options {
         Options {
             initial_capacity: 100,
             message_buffer_capacity: 20,
+            keep_finished: None,
+            message_sink: None,
         }
     }
 }
@@ -113,6 +269,8 @@ impl From<Options> for Root {
         Options {
             initial_capacity,
             message_buffer_capacity,
+            keep_finished,
+            message_sink,
         }: Options,
     ) -> Self {
         Root {
@@ -121,6 +279,15 @@ impl From<Options> for Root {
                 key: Key::default(),
                 tree: Arc::new(DashMap::with_capacity(initial_capacity)),
                 messages: Arc::new(Mutex::new(MessageRingBuffer::with_capacity(message_buffer_capacity))),
+                message_sink: message_sink.map(|sink| Arc::new(Mutex::new(SinkWriter::new(sink)))),
+                step: StepCounter::default(),
+                keep_finished,
+                changes: Arc::new(ChangeTracker::default()),
+                cancel_requested: Default::default(),
+                pause: Default::default(),
+                finished_children: Default::default(),
+                finished_siblings: Default::default(),
+                finished: Default::default(),
             })),
         }
     }
@@ -139,6 +306,10 @@ impl crate::Root for Root {
         self.sorted_snapshot(out)
     }
 
+    fn changes_since(&self, generation: Option<Generation>, out: &mut Changes) {
+        self.changes_since(generation, out)
+    }
+
     fn copy_messages(&self, out: &mut Vec<Message>) {
         self.copy_messages(out)
     }
@@ -146,4 +317,20 @@ impl crate::Root for Root {
     fn copy_new_messages(&self, out: &mut Vec<Message>, prev: Option<MessageCopyState>) -> MessageCopyState {
         self.copy_new_messages(out, prev)
     }
+
+    fn copy_message_history(&self, out: &mut Vec<Message>, max: usize) {
+        self.copy_message_history(out, max)
+    }
+
+    fn request_cancel(&self, key: &Key) {
+        self.request_cancel(key)
+    }
+
+    fn request_pause(&self, key: &Key) {
+        self.request_pause(key)
+    }
+
+    fn request_resume(&self, key: &Key) {
+        self.request_resume(key)
+    }
 }
@@ -0,0 +1,331 @@
+use crate::messages::{Field, FieldValue, Message, MessageLevel};
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    time::{Duration, UNIX_EPOCH},
+};
+
+/// The format in which a [`MessageSink`] writes messages, one per line.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum MessageFormat {
+    /// Tab-separated plain text with the time in seconds since the UNIX epoch, the level, the origin, the message
+    /// and one `key=value` column per field. Tabs, newlines and backslashes are escaped.
+    ///
+    /// Field values are read back as strings, and the task key and name path of messages are not retained.
+    Text,
+    /// One JSON object per line, as serialized by `serde`. Field values retain their type when read back.
+    #[cfg(feature = "serde")]
+    JsonLines,
+}
+
+/// Configures a file to which all messages are written as they are pushed into the tree.
+///
+/// Once the file would grow beyond `max_file_size`, it is renamed by appending `.1` to its name, shifting
+/// previously rotated files to `.2`, `.3` and so forth, while the oldest ones beyond `max_rotated_files` are deleted.
+///
+/// As opposed to the in-memory message buffer, this allows to keep all messages, and to [scroll back](crate::Root::copy_message_history())
+/// past the ones which were overwritten already.
+/// ```rust
+/// use prodash::{tree::MessageSink, TreeOptions};
+/// let tree = TreeOptions {
+///     message_sink: Some(MessageSink::new(std::env::temp_dir().join("messages.log"))),
+///     ..TreeOptions::default()
+/// }
+/// .create();
+/// ```
+#[derive(Clone, Debug)]
+pub struct MessageSink {
+    /// The file to append messages to. It is created if it doesn't exist.
+    pub path: PathBuf,
+    /// The format in which to write messages.
+    pub format: MessageFormat,
+    /// The size in bytes at which the file is rotated.
+    pub max_file_size: u64,
+    /// The amount of rotated files to keep. If 0, the file is truncated instead of rotated.
+    pub max_rotated_files: usize,
+}
+
+impl MessageSink {
+    /// Write plain text messages to the file at `path`, rotating it every 10MB and keeping up to 3 rotated files.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        MessageSink {
+            path: path.into(),
+            format: MessageFormat::Text,
+            max_file_size: 10 * 1024 * 1024,
+            max_rotated_files: 3,
+        }
+    }
+
+    /// The path of the rotated file with the given `number`, with 0 being the current file.
+    fn rotated_path(&self, number: usize) -> PathBuf {
+        if number == 0 {
+            return self.path.clone();
+        }
+        let mut path = OsString::from(self.path.as_os_str());
+        path.push(format!(".{}", number));
+        path.into()
+    }
+}
+
+/// Writes messages to the file configured in a [`MessageSink`], opening it lazily.
+///
+/// Writes are buffered, and flushed when the file is rotated, when the history is read and when we are dropped.
+#[derive(Debug)]
+pub(crate) struct SinkWriter {
+    sink: MessageSink,
+    file: Option<BufWriter<File>>,
+    size: u64,
+    line: Vec<u8>,
+}
+
+impl SinkWriter {
+    pub(crate) fn new(sink: MessageSink) -> Self {
+        SinkWriter {
+            sink,
+            file: None,
+            size: 0,
+            line: Vec::new(),
+        }
+    }
+
+    /// Write `message` to the file.
+    ///
+    /// Errors are ignored as messages shouldn't fail, but the file is reopened with the next message.
+    pub(crate) fn write(&mut self, message: &Message) {
+        if self.try_write(message).is_err() {
+            self.file = None;
+        }
+    }
+
+    fn try_write(&mut self, message: &Message) -> io::Result<()> {
+        self.line.clear();
+        encode(self.sink.format, message, &mut self.line)?;
+        if self.file.is_some() && self.size > 0 && self.size + self.line.len() as u64 > self.sink.max_file_size {
+            self.rotate();
+        }
+        let file = match self.file.as_mut() {
+            Some(file) => file,
+            None => {
+                let file = OpenOptions::new().create(true).append(true).open(&self.sink.path)?;
+                self.size = file.metadata()?.len();
+                self.file.get_or_insert(BufWriter::new(file))
+            }
+        };
+        file.write_all(&self.line)?;
+        self.size += self.line.len() as u64;
+        Ok(())
+    }
+
+    /// Delete the oldest file and shift all others by one, or delete the current file if no rotated files are kept.
+    fn rotate(&mut self) {
+        if let Some(mut file) = self.file.take() {
+            file.flush().ok();
+        }
+        let max = self.sink.max_rotated_files;
+        fs::remove_file(self.sink.rotated_path(max)).ok();
+        for number in (0..max).rev() {
+            fs::rename(self.sink.rotated_path(number), self.sink.rotated_path(number + 1)).ok();
+        }
+    }
+
+    /// Flush all messages written so far and return the paths of all files that may contain them, newest first,
+    /// to be passed to [`read_history()`] once we are unlocked.
+    pub(crate) fn history_paths(&mut self) -> Vec<PathBuf> {
+        if let Some(file) = self.file.as_mut() {
+            if file.flush().is_err() {
+                self.file = None;
+            }
+        }
+        (0..=self.sink.max_rotated_files)
+            .map(|number| self.sink.rotated_path(number))
+            .collect()
+    }
+}
+
+/// Read up to `max` of the most recent messages from the files at `paths`, newest first, into `out`, ordered from
+/// oldest to newest.
+///
+/// Only the tail of each file is read as far as needed, and lines which can't be parsed are skipped.
+pub(crate) fn read_history(paths: &[PathBuf], out: &mut Vec<Message>, max: usize) -> io::Result<()> {
+    out.clear();
+    let mut chunks = Vec::new();
+    let mut count = 0;
+    for path in paths {
+        if count >= max {
+            break;
+        }
+        let messages = match read_last_messages(path, max - count) {
+            Ok(messages) => messages,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => break,
+            Err(err) => return Err(err),
+        };
+        count += messages.len();
+        chunks.push(messages);
+    }
+    for chunk in chunks.into_iter().rev() {
+        out.extend(chunk.into_iter().rev());
+    }
+    Ok(())
+}
+
+/// Read up to `max` of the last messages in the file at `path`, newest first, reading it backwards in blocks.
+fn read_last_messages(path: &Path, max: usize) -> io::Result<Vec<Message>> {
+    const BLOCK_SIZE: u64 = 64 * 1024;
+    let mut file = File::open(path)?;
+    let mut end = file.metadata()?.len();
+    let mut messages = Vec::new();
+    // The beginning of the block read previously, up to and including the end of its first line, which is incomplete
+    // unless the block started at the beginning of the file.
+    let mut partial_line = Vec::new();
+    while end > 0 && messages.len() < max {
+        let start = end.saturating_sub(BLOCK_SIZE);
+        let mut block = vec![0; (end - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut block)?;
+        block.append(&mut partial_line);
+        end = start;
+        let first_line_end = match block.iter().position(|b| *b == b'\n') {
+            _ if start == 0 => 0,
+            Some(pos) => pos + 1,
+            None => {
+                partial_line = block;
+                continue;
+            }
+        };
+        for line in block[first_line_end..].split(|b| *b == b'\n').rev() {
+            if messages.len() == max {
+                break;
+            }
+            messages.extend(std::str::from_utf8(line).ok().and_then(decode));
+        }
+        block.truncate(first_line_end);
+        partial_line = block;
+    }
+    Ok(messages)
+}
+
+fn encode(format: MessageFormat, message: &Message, out: &mut Vec<u8>) -> io::Result<()> {
+    match format {
+        MessageFormat::Text => {
+            let time = message.time.duration_since(UNIX_EPOCH).unwrap_or_default();
+            write!(
+                out,
+                "{}.{:09}\t{}\t",
+                time.as_secs(),
+                time.subsec_nanos(),
+                level_name(message.level)
+            )?;
+            escape(&message.origin, false, out);
+            out.push(b'\t');
+            escape(&message.message, false, out);
+            for field in &message.fields {
+                out.push(b'\t');
+                escape(&field.key, true, out);
+                out.push(b'=');
+                escape(&field.value.to_string(), false, out);
+            }
+        }
+        #[cfg(feature = "serde")]
+        MessageFormat::JsonLines => serde_json::to_writer(&mut *out, message)?,
+    }
+    out.push(b'\n');
+    Ok(())
+}
+
+/// Parse a `line` in any format, as the format of previously written files isn't necessarily the one configured now.
+fn decode(line: &str) -> Option<Message> {
+    #[cfg(feature = "serde")]
+    {
+        if line.starts_with('{') {
+            return serde_json::from_str(line).ok();
+        }
+    }
+    let mut columns = line.split('\t');
+    let time = {
+        let (secs, nanos) = columns.next()?.split_once('.')?;
+        UNIX_EPOCH.checked_add(Duration::new(secs.parse().ok()?, nanos.parse().ok()?))?
+    };
+    let level = match columns.next()? {
+        "info" => MessageLevel::Info,
+        "failure" => MessageLevel::Failure,
+        "success" => MessageLevel::Success,
+        "warning" => MessageLevel::Warning,
+        "debug" => MessageLevel::Debug,
+        _ => return None,
+    };
+    let origin = unescape(columns.next()?);
+    let message = unescape(columns.next()?);
+    let fields = columns
+        .map(|column| {
+            let mut key_end = column.len();
+            let mut escaped = false;
+            for (index, c) in column.char_indices() {
+                match c {
+                    '=' if !escaped => {
+                        key_end = index;
+                        break;
+                    }
+                    '\\' => escaped = !escaped,
+                    _ => escaped = false,
+                }
+            }
+            Field::new(
+                unescape(&column[..key_end]),
+                FieldValue::String(unescape(column.get(key_end + 1..).unwrap_or_default())),
+            )
+        })
+        .collect();
+    Some(Message {
+        time,
+        level,
+        origin,
+        key: None,
+        path: Vec::new(),
+        message,
+        fields,
+    })
+}
+
+fn level_name(level: MessageLevel) -> &'static str {
+    match level {
+        MessageLevel::Info => "info",
+        MessageLevel::Failure => "failure",
+        MessageLevel::Success => "success",
+        MessageLevel::Warning => "warning",
+        MessageLevel::Debug => "debug",
+    }
+}
+
+fn escape(input: &str, is_key: bool, out: &mut Vec<u8>) {
+    for c in input.chars() {
+        match c {
+            '\\' => out.extend_from_slice(b"\\\\"),
+            '\t' => out.extend_from_slice(b"\\t"),
+            '\n' => out.extend_from_slice(b"\\n"),
+            '\r' => out.extend_from_slice(b"\\r"),
+            '=' if is_key => out.extend_from_slice(b"\\="),
+            c => out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+}
+
+fn unescape(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(c) => out.push(c),
+            None => {}
+        }
+    }
+    out
+}
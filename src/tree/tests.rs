@@ -111,6 +111,55 @@ mod message_buffer {
             buf.copy_new(&mut out, state);
             assert_messages(&out, &["2", "3"]);
         }
+
+        #[test]
+        fn states_of_other_buffers_are_treated_like_no_state() {
+            let mut other = MessageRingBuffer::with_capacity(4);
+            for msg in &["one", "two", "three", "four", "five"] {
+                push(&mut other, *msg);
+            }
+            let mut out = Vec::new();
+            let ahead = other.copy_new(&mut out, None);
+
+            let mut buf = MessageRingBuffer::with_capacity(2);
+            buf.copy_new(&mut out, Some(ahead.clone()));
+            assert_messages(&out, &[]);
+
+            push(&mut buf, "a");
+            push(&mut buf, "b");
+            push(&mut buf, "c");
+            buf.copy_new(&mut out, Some(ahead));
+            assert_messages(&out, &["b", "c"]);
+        }
+
+        #[test]
+        fn dropped_messages_are_counted() {
+            let mut buf = MessageRingBuffer::with_capacity(2);
+            let mut out = Vec::new();
+            push(&mut buf, "one");
+            let state = buf.copy_new(&mut out, None);
+            assert_eq!(state.dropped(), 0);
+
+            for msg in &["two", "three", "four", "five"] {
+                push(&mut buf, *msg);
+            }
+            let state = buf.copy_new(&mut out, Some(state));
+            assert_messages(&out, &["four", "five"]);
+            assert_eq!(state.dropped(), 2, "'two' and 'three' were overwritten");
+
+            push(&mut buf, "six");
+            assert_eq!(
+                buf.copy_new(&mut out, Some(state)).dropped(),
+                0,
+                "the count is only about the latest copy"
+            );
+
+            assert_eq!(
+                buf.copy_new(&mut out, None).dropped(),
+                4,
+                "without state, all overwritten messages are dropped"
+            );
+        }
     }
 }
 
@@ -122,7 +171,7 @@ mod key_adjacency {
 
     fn to_kv(keys: &[Key]) -> Vec<(Key, Task)> {
         let mut v: Vec<_> = keys.iter().map(|k| (k.to_owned(), Task::default())).collect();
-        v.sort_by_key(|v| v.0);
+        v.sort_by(|a, b| a.0.cmp(&b.0));
         v
     }
 
@@ -136,10 +185,10 @@ mod key_adjacency {
         let p2 = r.add_child(2);
         to_kv(
             &[
-                p1,
+                p1.clone(),
                 p1.add_child(1),
                 p1.add_child(2),
-                p2,
+                p2.clone(),
                 p2.add_child(1),
                 p2.add_child(2),
             ][..],
@@ -150,7 +199,7 @@ mod key_adjacency {
         let r = Key::default();
         let p1 = r.add_child(1);
         let p2 = p1.add_child(2);
-        to_kv(&[p1, p2, p2.add_child(1)][..])
+        to_kv(&[p1, p2.clone(), p2.add_child(1)][..])
     }
 
     fn root_with_three_levels_two_siblings_on_level_2() -> Vec<(Key, Task)> {
@@ -158,72 +207,42 @@ mod key_adjacency {
         let p1 = r.add_child(1);
         let p11 = p1.add_child(1);
         let p12 = p1.add_child(2);
-        to_kv(&[p1, p11, p11.add_child(1), p12, p12.add_child(1)][..])
+        to_kv(&[p1, p11.clone(), p11.add_child(1), p12.clone(), p12.add_child(1)][..])
     }
 
     #[test]
     fn root_level() {
         let entries = root_with_two_children();
-        assert_eq!(
-            Key::adjacency(&entries, 0),
-            Adjacency(AboveAndBelow, NotFound, NotFound, NotFound)
-        );
-        assert_eq!(
-            Key::adjacency(&entries, 1),
-            Adjacency(Above, NotFound, NotFound, NotFound)
-        );
+        assert_eq!(Key::adjacency(&entries, 0), Adjacency(vec![AboveAndBelow]));
+        assert_eq!(Key::adjacency(&entries, 1), Adjacency(vec![Above]));
     }
 
     #[test]
     fn level_2_two_siblings() {
         let entries = root_with_two_children_with_two_children();
-        assert_eq!(
-            Key::adjacency(&entries, 0),
-            Adjacency(AboveAndBelow, NotFound, NotFound, NotFound)
-        );
+        assert_eq!(Key::adjacency(&entries, 0), Adjacency(vec![AboveAndBelow]));
         {
             assert_eq!(
                 Key::adjacency(&entries, 1),
-                Adjacency(AboveAndBelow, AboveAndBelow, NotFound, NotFound)
-            );
-            assert_eq!(
-                Key::adjacency(&entries, 2),
-                Adjacency(AboveAndBelow, Above, NotFound, NotFound)
+                Adjacency(vec![AboveAndBelow, AboveAndBelow])
             );
+            assert_eq!(Key::adjacency(&entries, 2), Adjacency(vec![AboveAndBelow, Above]));
         }
-        assert_eq!(
-            Key::adjacency(&entries, 3),
-            Adjacency(Above, NotFound, NotFound, NotFound)
-        );
+        assert_eq!(Key::adjacency(&entries, 3), Adjacency(vec![Above]));
         {
-            assert_eq!(
-                Key::adjacency(&entries, 4),
-                Adjacency(NotFound, AboveAndBelow, NotFound, NotFound)
-            );
-            assert_eq!(
-                Key::adjacency(&entries, 5),
-                Adjacency(NotFound, Above, NotFound, NotFound)
-            );
+            assert_eq!(Key::adjacency(&entries, 4), Adjacency(vec![NotFound, AboveAndBelow]));
+            assert_eq!(Key::adjacency(&entries, 5), Adjacency(vec![NotFound, Above]));
         }
     }
 
     #[test]
     fn level_3_single_sibling() {
         let entries = root_with_three_levels();
-        assert_eq!(
-            Key::adjacency(&entries, 0),
-            Adjacency(Above, NotFound, NotFound, NotFound)
-        );
+        assert_eq!(Key::adjacency(&entries, 0), Adjacency(vec![Above]));
         {
-            assert_eq!(
-                Key::adjacency(&entries, 1),
-                Adjacency(NotFound, Above, NotFound, NotFound)
-            );
+            assert_eq!(Key::adjacency(&entries, 1), Adjacency(vec![NotFound, Above]));
             {
-                assert_eq!(
-                    Key::adjacency(&entries, 2),
-                    Adjacency(NotFound, NotFound, Above, NotFound)
-                );
+                assert_eq!(Key::adjacency(&entries, 2), Adjacency(vec![NotFound, NotFound, Above]));
             }
         }
     }
@@ -232,31 +251,19 @@ mod key_adjacency {
     fn level_3_two_siblings() {
         let entries = root_with_three_levels_two_siblings_on_level_2();
         {
-            assert_eq!(
-                Key::adjacency(&entries, 0),
-                Adjacency(Above, NotFound, NotFound, NotFound)
-            );
+            assert_eq!(Key::adjacency(&entries, 0), Adjacency(vec![Above]));
             {
-                assert_eq!(
-                    Key::adjacency(&entries, 1),
-                    Adjacency(NotFound, AboveAndBelow, NotFound, NotFound)
-                );
+                assert_eq!(Key::adjacency(&entries, 1), Adjacency(vec![NotFound, AboveAndBelow]));
                 {
                     assert_eq!(
                         Key::adjacency(&entries, 2),
-                        Adjacency(NotFound, AboveAndBelow, Above, NotFound)
+                        Adjacency(vec![NotFound, AboveAndBelow, Above])
                     );
                 }
 
-                assert_eq!(
-                    Key::adjacency(&entries, 3),
-                    Adjacency(NotFound, Above, NotFound, NotFound)
-                );
+                assert_eq!(Key::adjacency(&entries, 3), Adjacency(vec![NotFound, Above]));
                 {
-                    assert_eq!(
-                        Key::adjacency(&entries, 4),
-                        Adjacency(NotFound, NotFound, Above, NotFound)
-                    );
+                    assert_eq!(Key::adjacency(&entries, 4), Adjacency(vec![NotFound, NotFound, Above]));
                 }
             }
         }
@@ -269,18 +276,1604 @@ mod key_adjacency {
             1,
             (Key::default().add_child(0).add_child(0).add_child(1), Task::default()),
         );
-        entries.sort_by_key(|v| v.0);
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
         assert_eq!(
             Key::adjacency(&entries, 0),
-            Adjacency(AboveAndBelow, NotFound, NotFound, NotFound),
+            Adjacency(vec![AboveAndBelow, NotFound, NotFound]),
+        );
+        assert_eq!(Key::adjacency(&entries, 1), Adjacency(vec![AboveAndBelow]));
+        assert_eq!(Key::adjacency(&entries, 2), Adjacency(vec![Above]));
+    }
+
+    fn root_with_five_levels_two_siblings_on_level_5() -> Vec<(Key, Task)> {
+        let r = Key::default();
+        let p1 = r.add_child(1);
+        let p2 = p1.add_child(1);
+        let p3 = p2.add_child(1);
+        let p4 = p3.add_child(1);
+        to_kv(&[p1, p2, p3, p4.clone(), p4.add_child(1), p4.add_child(2)][..])
+    }
+
+    #[test]
+    fn level_5_two_siblings() {
+        let entries = root_with_five_levels_two_siblings_on_level_5();
+        assert_eq!(
+            Key::adjacency(&entries, 3),
+            Adjacency(vec![NotFound, NotFound, NotFound, Above])
+        );
+        assert_eq!(
+            Key::adjacency(&entries, 4),
+            Adjacency(vec![NotFound, NotFound, NotFound, NotFound, AboveAndBelow])
+        );
+        assert_eq!(
+            Key::adjacency(&entries, 5),
+            Adjacency(vec![NotFound, NotFound, NotFound, NotFound, Above])
+        );
+        assert_eq!(Key::adjacency(&entries, 5).level(), 5);
+    }
+}
+
+mod key {
+    use crate::progress::{key::Id, Key};
+
+    #[test]
+    fn nesting_is_not_limited() {
+        let mut key = Key::default();
+        for level in 1..=10 {
+            key = key.add_child(level as Id);
+            assert_eq!(key.level(), level);
+        }
+    }
+
+    #[test]
+    fn parents_sort_before_their_children_and_following_siblings() {
+        let r = Key::default();
+        let p1 = r.add_child(1);
+        let deep_child = p1.add_child(0).add_child(0).add_child(0).add_child(5);
+        let p2 = r.add_child(2);
+        assert!(r < p1);
+        assert!(p1 < deep_child);
+        assert!(deep_child < p2);
+        assert!(deep_child.shares_parent_with(&p1, 1));
+        assert!(!deep_child.shares_parent_with(&p2, 1));
+    }
+
+    #[test]
+    fn parents_contain_their_descendants() {
+        let r = Key::default();
+        let p1 = r.add_child(1);
+        let child = p1.add_child(0);
+        let p2 = r.add_child(2);
+        assert_eq!(child.parent(), Some(p1.clone()));
+        assert_eq!(p1.parent(), Some(r.clone()));
+        assert_eq!(r.parent(), None);
+        assert!(p1.contains(&child));
+        assert!(p1.contains(&p1));
+        assert!(!child.contains(&p1));
+        assert!(!p2.contains(&child));
+    }
+}
+
+mod item {
+    use crate::{
+        messages::MessageLevel,
+        progress::{key::Id, Outcome, State},
+        tree::KeepFinished,
+        TreeOptions,
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn messages_have_the_level_of_their_helper() {
+        let root = crate::Tree::new();
+        let mut task = root.add_child("task");
+        task.debug("d");
+        task.info("i");
+        task.warn("w");
+        task.fail("f");
+        task.done("s");
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        assert_eq!(
+            messages.iter().map(|m| m.level).collect::<Vec<_>>(),
+            vec![
+                MessageLevel::Debug,
+                MessageLevel::Info,
+                MessageLevel::Warning,
+                MessageLevel::Failure,
+                MessageLevel::Success
+            ]
+        );
+    }
+
+    #[test]
+    fn messages_can_carry_typed_fields() {
+        use crate::{messages::Field, Progress};
+        let root = crate::Tree::new();
+        let mut task = root.add_child("task");
+        Progress::message_with_fields(
+            &mut task,
+            MessageLevel::Failure,
+            "could not read",
+            vec![
+                Field::new("path", "a.txt"),
+                Field::new("code", -2),
+                Field::new("bytes", 3usize),
+                Field::new("ratio", 0.5),
+                Field::new("retry", true),
+            ],
+        );
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        assert_eq!(messages[0].origin, "task");
+        assert_eq!(
+            messages[0].fields.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["path=a.txt", "code=-2", "bytes=3", "ratio=0.5", "retry=true"]
+        );
+    }
+
+    #[test]
+    fn the_maximum_can_change_without_losing_progress() {
+        use crate::{progress::DoOrDiscard, Progress};
+        let root = crate::Tree::new();
+        let mut task = root.add_child("task");
+        assert_eq!(task.set_max(Some(5)), None, "uninitialized tasks have no maximum");
+        task.inc_max(5);
+        assert_eq!(task.max(), None);
+
+        task.init(Some(10), None);
+        task.set(4);
+        task.blocked("discovering", None);
+        assert_eq!(task.set_max(Some(20)), Some(10));
+        task.inc_max(5);
+        assert_eq!(task.max(), Some(25));
+        assert_eq!(task.step(), Some(4));
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        assert_eq!(
+            snapshot[0].1.progress.as_ref().map(|p| p.state.clone()),
+            Some(State::Blocked("discovering".into(), None)),
+            "the state is retained"
+        );
+
+        task.set_max(None);
+        task.inc_max(3);
+        assert_eq!(task.max(), Some(3), "unbounded tasks become bounded");
+
+        let mut task = DoOrDiscard::from(Some(task));
+        assert_eq!(Progress::set_max(&mut task, Some(7)), Some(3));
+        Progress::inc_max(&mut task, 1);
+        assert_eq!(Progress::max(&task), Some(8));
+        assert_eq!(Progress::step(&task), 4);
+    }
+
+    #[test]
+    fn cancellation_is_requested_for_a_task_and_its_children() {
+        use crate::{progress::DoOrDiscard, Progress};
+        let root = crate::Tree::new();
+        let mut parent = root.add_child("parent");
+        let child = parent.add_child("child");
+        let sibling = root.add_child("sibling");
+        assert!(!parent.is_cancel_requested());
+
+        root.request_cancel(&parent.key);
+        assert!(parent.is_cancel_requested());
+        assert!(
+            child.is_cancel_requested(),
+            "children are cancelled along with their parent"
+        );
+        assert!(!sibling.is_cancel_requested());
+        let late_child = parent.add_child("late child");
+        assert!(
+            late_child.is_cancel_requested(),
+            "children added after the request inherit it"
+        );
+
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        assert_eq!(
+            snapshot
+                .iter()
+                .map(|(_, t)| (t.name.as_str(), t.cancel_requested))
+                .collect::<Vec<_>>(),
+            vec![
+                ("parent", true),
+                ("child", true),
+                ("late child", true),
+                ("sibling", false)
+            ]
+        );
+        assert!(Progress::is_cancel_requested(&DoOrDiscard::from(Some(child))));
+    }
+
+    #[test]
+    fn paused_tasks_and_their_children_wait_until_resumed() {
+        use crate::progress::State;
+        let root = crate::Tree::new();
+        let mut parent = root.add_child("parent");
+        parent.init(None, None);
+        let mut child = parent.add_child("child");
+        child.init(None, None);
+        assert!(!child.is_paused());
+        child.wait_if_paused();
+
+        root.request_pause(&parent.key);
+        assert!(parent.is_paused());
+        assert!(child.is_paused(), "children are paused along with their parent");
+        assert!(parent.add_child("late").is_paused(), "new children start out paused");
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        assert_eq!(
+            snapshot[1].1.progress.as_ref().map(|p| p.state.clone()),
+            Some(State::Paused)
+        );
+
+        let waiting = std::thread::spawn(move || {
+            child.wait_if_paused();
+            child
+        });
+        let wait_async = parent.wait_if_paused_async();
+        let resuming = std::thread::spawn({
+            let root = root.clone();
+            let key = parent.key.clone();
+            move || {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+                root.request_resume(&key);
+            }
+        });
+        futures::executor::block_on(wait_async);
+        resuming.join().expect("no panic");
+        let child = waiting.join().expect("no panic");
+        assert!(!child.is_paused());
+        root.sorted_snapshot(&mut snapshot);
+        assert_eq!(
+            snapshot[1].1.progress.as_ref().map(|p| p.state.clone()),
+            Some(State::Running)
+        );
+    }
+
+    #[test]
+    fn paused_tasks_stop_waiting_when_cancelled() {
+        let root = crate::Tree::new();
+        let mut parent = root.add_child("parent");
+        let child = parent.add_child("child");
+        root.request_pause(&parent.key);
+
+        let waiting = std::thread::spawn(move || {
+            child.wait_if_paused();
+            child
+        });
+        let wait_async = parent.wait_if_paused_async();
+        let cancelling = std::thread::spawn({
+            let root = root.clone();
+            let key = parent.key.clone();
+            move || {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+                root.request_cancel(&key);
+            }
+        });
+        futures::executor::block_on(wait_async);
+        cancelling.join().expect("no panic");
+        let child = waiting.join().expect("no panic");
+        assert!(child.is_cancel_requested());
+        assert!(child.is_paused(), "cancellation doesn't resume");
+        parent.add_child("late").wait_if_paused();
+    }
+
+    #[test]
+    fn tasks_can_carry_additional_counters() {
+        let root = crate::Tree::new();
+        let mut task = root.add_child("task");
+        task.init(Some(10), Some("objects".into()));
+        let bytes = task.add_counter("bytes", None, None);
+        let lines = task.add_counter("lines", Some(100), Some("lines".into()));
+        bytes.inc_by(1024);
+        lines.set(42);
+        task.set(3);
+
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        let counters: Vec<_> = snapshot[0]
+            .1
+            .counters
+            .iter()
+            .map(|c| (c.name.as_str(), c.step, c.done_at, c.display(None).to_string()))
+            .collect();
+        assert_eq!(
+            counters,
+            vec![
+                ("bytes", 1024, None, "1024 bytes".to_string()),
+                ("lines", 42, Some(100), "42/100 lines".to_string())
+            ]
+        );
+        assert_eq!(snapshot[0].1.progress.as_ref().map(|p| p.step), Some(3));
+
+        let again = task.add_counter("bytes", Some(2048), None);
+        again.inc();
+        root.sorted_snapshot(&mut snapshot);
+        assert_eq!(
+            snapshot[0].1.counters.len(),
+            2,
+            "counters with the same name are reused"
+        );
+        assert_eq!(
+            (snapshot[0].1.counters[0].step, snapshot[0].1.counters[0].done_at),
+            (1025, Some(2048))
+        );
+    }
+
+    #[test]
+    fn blocked_and_halted_reasons_can_be_created_at_runtime() {
+        let root = crate::Tree::new();
+        let mut task = root.add_child("task");
+        task.init(None, None);
+        task.blocked(format!("retry {}/{}", 3, 5), None);
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        assert_eq!(
+            snapshot[0].1.progress.as_ref().map(|p| p.state.clone()),
+            Some(State::Blocked("retry 3/5".into(), None))
+        );
+
+        task.halted(String::from("waiting for lock on refs/heads/main"), None);
+        root.sorted_snapshot(&mut snapshot);
+        assert_eq!(
+            snapshot[0].1.progress.as_ref().map(|p| p.state.clone()),
+            Some(State::Halted("waiting for lock on refs/heads/main".into(), None))
+        );
+    }
+
+    #[test]
+    fn messages_know_the_key_and_name_path_of_their_task() {
+        let root = crate::Tree::new();
+        let mut parent = root.add_child("parent");
+        let mut child = parent.add_child("child");
+        parent.info("from parent");
+        child.info("from child");
+
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        assert_eq!(messages[0].key, Some(parent.key.clone()));
+        assert_eq!(messages[1].key, Some(child.key.clone()));
+        assert_eq!(messages[1].origin, "child");
+        assert_eq!(messages[1].path, vec!["parent", "child"]);
+        assert_eq!(messages[1].display_path(" › ").to_string(), "parent › child");
+
+        let mut buf = crate::messages::MessageRingBuffer::with_capacity(1);
+        buf.push_overwrite(MessageLevel::Info, "origin".into(), "without task");
+        buf.copy_all(&mut messages);
+        assert_eq!(messages[0].key, None);
+        assert_eq!(
+            messages[0].display_path(" › ").to_string(),
+            "origin",
+            "the origin is used if the path is unknown"
+        );
+    }
+
+    #[test]
+    fn wrapped_child_ids_never_alias_live_children() {
+        let root = crate::Tree::new();
+        let mut parent = root.add_child("parent");
+        let mut first = parent.add_child("first");
+        first.init(Some(10), None);
+        first.set(3);
+
+        parent.highest_child_id = Id::MAX;
+        let last = parent.add_child("last");
+        let wrapped = parent.add_child("wrapped");
+        assert_eq!(last.key.level(), 2);
+        assert_ne!(wrapped.key, first.key, "the id of the live child is skipped");
+        assert_ne!(wrapped.key, last.key);
+        assert_eq!(root.num_tasks(), 4);
+
+        drop(wrapped);
+        assert_eq!(
+            first.name().as_deref(),
+            Some("first"),
+            "dropping the wrapped child keeps the first one"
+        );
+        assert_eq!(first.step(), Some(3));
+    }
+
+    #[test]
+    fn shared_counters_are_visible_in_snapshots() {
+        let root = crate::Tree::new();
+        let mut item = root.add_child("work");
+        item.init(Some(4000), None);
+        let counter = item.counter();
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let counter = counter.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..1000 {
+                        counter.inc();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("no panic");
+        }
+        assert_eq!(item.step(), Some(4000));
+
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        assert_eq!(snapshot[0].1.progress.as_ref().map(|p| p.step), Some(4000));
+
+        let copy = root.deep_clone();
+        counter.set(1);
+        copy.sorted_snapshot(&mut snapshot);
+        assert_eq!(
+            snapshot[0].1.progress.as_ref().map(|p| p.step),
+            Some(4000),
+            "deep clones don't share counters"
+        );
+    }
+
+    fn snapshot(root: &crate::Tree) -> Vec<(String, Option<State>)> {
+        let mut out = Vec::new();
+        root.sorted_snapshot(&mut out);
+        out.into_iter()
+            .map(|(_, task)| (task.name, task.progress.map(|p| p.state)))
+            .collect()
+    }
+
+    #[test]
+    fn finished_tasks_are_removed_by_default() {
+        let root = crate::Tree::new();
+        let mut item = root.add_child("work");
+        item.init(None, None);
+        item.finish(Outcome::Failed);
+        assert_eq!(
+            snapshot(&root),
+            vec![("work".into(), Some(State::Finished(Outcome::Failed)))]
+        );
+        drop(item);
+        assert_eq!(snapshot(&root), vec![]);
+    }
+
+    #[test]
+    fn finished_tasks_can_be_kept_for_some_time() {
+        let root = TreeOptions {
+            keep_finished: Some(KeepFinished::For(Duration::from_millis(50))),
+            ..TreeOptions::default()
+        }
+        .create();
+        let mut item = root.add_child("work");
+        item.init(None, None);
+        drop(item);
+        assert_eq!(
+            snapshot(&root),
+            vec![("work".into(), Some(State::Finished(Outcome::Succeeded)))],
+            "items that are dropped without outcome succeeded"
+        );
+        std::thread::sleep(Duration::from_millis(60));
+        drop(root.add_child("other"));
+        assert_eq!(
+            root.num_tasks(),
+            1,
+            "expired tasks are removed when others are dropped, without waiting for a snapshot"
+        );
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(snapshot(&root), vec![]);
+    }
+
+    #[test]
+    fn finished_tasks_can_be_kept_until_their_parent_drops() {
+        let root = TreeOptions {
+            keep_finished: Some(KeepFinished::UntilParentDrops {
+                max_top_level_tasks: 100,
+            }),
+            ..TreeOptions::default()
+        }
+        .create();
+        let mut parent = root.add_child("parent");
+        let mut cancelled = parent.add_child("cancelled");
+        cancelled.init(None, None);
+        cancelled.finish(Outcome::Cancelled);
+        let mut grandchild = cancelled.add_child("grandchild");
+        grandchild.init(None, None);
+        drop(grandchild);
+        assert_eq!(
+            snapshot(&root),
+            vec![
+                ("parent".into(), None),
+                ("cancelled".into(), Some(State::Finished(Outcome::Cancelled))),
+                ("grandchild".into(), Some(State::Finished(Outcome::Succeeded))),
+            ]
+        );
+        drop(cancelled);
+        let _running = parent.add_child("running");
+        assert_eq!(
+            snapshot(&root),
+            vec![
+                ("parent".into(), None),
+                ("cancelled".into(), Some(State::Finished(Outcome::Cancelled))),
+                ("running".into(), None),
+            ],
+            "finished descendants are removed when their parent drops"
+        );
+        drop(parent);
+        assert_eq!(
+            snapshot(&root),
+            vec![("parent".into(), None), ("running".into(), None)],
+            "only finished descendants of the parent are removed, while the parent itself is kept"
+        );
+    }
+
+    #[test]
+    fn finished_top_level_tasks_are_capped_and_orphans_are_removed_when_they_finish() {
+        let root = TreeOptions {
+            keep_finished: Some(KeepFinished::UntilParentDrops { max_top_level_tasks: 2 }),
+            ..TreeOptions::default()
+        }
+        .create();
+        for n in 0..3 {
+            root.add_child(n.to_string());
+        }
+        let names: Vec<_> = snapshot(&root).into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["1", "2"], "the oldest finished task is removed");
+
+        let root = TreeOptions {
+            keep_finished: Some(KeepFinished::UntilParentDrops {
+                max_top_level_tasks: 100,
+            }),
+            ..TreeOptions::default()
+        }
+        .create();
+        let mut grandparent = root.add_child("grandparent");
+        let mut parent = grandparent.add_child("parent");
+        let child = parent.add_child("child");
+        drop(parent);
+        assert_eq!(
+            snapshot(&root),
+            vec![
+                ("grandparent".into(), None),
+                ("parent".into(), None),
+                ("child".into(), None)
+            ]
+        );
+        drop(child);
+        assert_eq!(
+            snapshot(&root),
+            vec![("grandparent".into(), None), ("parent".into(), None)],
+            "nobody is left to remove the child once its parent was dropped"
+        );
+        drop(grandparent);
+        assert_eq!(snapshot(&root), vec![("grandparent".into(), None)]);
+    }
+}
+
+mod value {
+    use crate::{
+        progress::{Outcome, State, Value},
+        unit::display::Throughput,
+    };
+    use std::time::{Duration, SystemTime};
+
+    fn half_done() -> Value {
+        Value {
+            step: 50,
+            done_at: Some(100),
+            initialized_at: Some(SystemTime::now() - Duration::from_secs(10)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn eta_uses_throughput_if_available() {
+        let tp = Throughput::new(10, Duration::from_secs(1));
+        assert_eq!(half_done().eta(Some(&tp)), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn eta_falls_back_to_average_rate_since_initialization() {
+        let eta = half_done().eta(None).expect("enough information");
+        assert!(
+            eta >= Duration::from_secs(10) && eta < Duration::from_secs(11),
+            "{:?}",
+            eta
+        );
+        assert!(half_done().elapsed().expect("initialized") >= Duration::from_secs(10));
+    }
+
+    #[test]
+    fn eta_is_unknown_for_unbounded_finished_or_idle_tasks() {
+        let unbounded = Value {
+            done_at: None,
+            ..half_done()
+        };
+        assert_eq!(unbounded.eta(None), None);
+        let finished = Value {
+            state: State::Finished(Outcome::Succeeded),
+            ..half_done()
+        };
+        assert_eq!(finished.eta(None), None);
+        let idle = Value { step: 0, ..half_done() };
+        assert_eq!(idle.eta(Some(&Throughput::new(0, Duration::from_secs(1)))), None);
+    }
+
+    #[test]
+    fn eta_is_unknown_if_it_is_too_large_to_represent() {
+        let barely_started = Value {
+            step: 1,
+            done_at: Some(usize::MAX),
+            ..half_done()
+        };
+        assert_eq!(barely_started.eta(None), None);
+        assert_eq!(
+            barely_started.eta(Some(&Throughput::new(1, Duration::from_secs(u64::MAX / 2)))),
+            None
+        );
+    }
+}
+
+mod roll_up {
+    use crate::{
+        progress::{self, Outcome, RollUp},
+        Tree,
+    };
+
+    fn rolled_up(root: &Tree) -> Vec<(String, Option<String>)> {
+        let mut out = Vec::new();
+        root.sorted_snapshot(&mut out);
+        progress::roll_up(&mut out);
+        out.into_iter()
+            .map(|(_, t)| {
+                let label = t.progress.map(|p| match p.unit {
+                    Some(unit) => format!("{}", unit.display(p.step, p.done_at, None)),
+                    None => format!("{}", p.step),
+                });
+                (t.name, label)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn count_shows_how_many_children_are_done() {
+        let root = Tree::new();
+        let mut parent = root.add_child("parent");
+        parent.set_roll_up(Some(RollUp::Count));
+        let mut bounded = parent.add_child("bounded");
+        bounded.init(Some(4), None);
+        bounded.set(4);
+        let mut finished = parent.add_child("finished");
+        finished.init(None, None);
+        finished.finish(Outcome::Failed);
+        let mut running = parent.add_child("running");
+        running.init(Some(4), None);
+        let _organizational = parent.add_child("no progress");
+
+        assert_eq!(
+            rolled_up(&root)[0],
+            ("parent".into(), Some("2 of 3 subtasks done".into())),
+            "children without progress are not counted"
+        );
+    }
+
+    #[test]
+    fn fraction_is_weighted_and_can_be_nested() {
+        let root = Tree::new();
+        let mut top = root.add_child("top");
+        top.set_roll_up(Some(RollUp::Fraction));
+        let mut middle = top.add_child("middle");
+        middle.set_roll_up(Some(RollUp::Fraction));
+        let mut heavy = middle.add_child("heavy");
+        heavy.init(Some(10), None);
+        heavy.set(5);
+        heavy.set_weight(3.0);
+        let mut light = middle.add_child("light");
+        light.init(None, None);
+        light.set(100);
+        let mut other = top.add_child("other");
+        other.init(Some(2), None);
+        other.set(2);
+
+        let entries = rolled_up(&root);
+        assert_eq!(entries[1], ("middle".into(), Some("37%".into())));
+        assert_eq!(
+            entries[0],
+            ("top".into(), Some("68%".into())),
+            "the parent sees the rolled up progress of its children"
+        );
+    }
+
+    #[test]
+    fn tasks_with_their_own_progress_are_left_alone() {
+        let root = Tree::new();
+        let mut parent = root.add_child("parent");
+        parent.set_roll_up(Some(RollUp::Count));
+        parent.init(Some(10), Some("items".into()));
+        parent.set(1);
+        parent.add_child("child").init(Some(1), None);
+
+        assert_eq!(rolled_up(&root)[0], ("parent".into(), Some("1/10 items".into())));
+    }
+}
+
+mod changes {
+    use crate::{progress::Changes, Tree};
+
+    fn names(changes: &Changes) -> Vec<&str> {
+        changes.updated.iter().map(|(_, t)| t.name.as_str()).collect()
+    }
+
+    #[test]
+    fn only_changed_tasks_are_reported_after_the_first_call() {
+        let root = Tree::new();
+        let mut a = root.add_child("a");
+        a.init(Some(10), None);
+        let b = root.add_child("b");
+        let counter = b.counter();
+        let mut changes = Changes::default();
+
+        root.changes_since(None, &mut changes);
+        assert!(changes.is_complete);
+        assert_eq!(names(&changes), vec!["a", "b"]);
+        let mut snapshot = Vec::new();
+        changes.apply_to(&mut snapshot);
+
+        let generation = changes.generation;
+        root.changes_since(Some(generation), &mut changes);
+        root.changes_since(Some(changes.generation), &mut changes);
+        assert!(!changes.is_complete);
+        assert!(
+            changes.updated.is_empty() && changes.removed.is_empty(),
+            "nothing changed"
+        );
+
+        a.inc();
+        counter.inc();
+        drop(b);
+        let _c = root.add_child("c");
+        root.changes_since(Some(changes.generation), &mut changes);
+        assert_eq!(
+            names(&changes),
+            vec!["a", "c"],
+            "changes to uninitialized counters aren't visible"
+        );
+        assert_eq!(changes.removed.len(), 1);
+
+        changes.apply_to(&mut snapshot);
+        let mut expected = Vec::new();
+        root.sorted_snapshot(&mut expected);
+        assert_eq!(
+            snapshot
+                .iter()
+                .map(|(k, t)| (k.clone(), t.name.clone(), t.progress.as_ref().map(|p| p.step)))
+                .collect::<Vec<_>>(),
+            expected
+                .iter()
+                .map(|(k, t)| (k.clone(), t.name.clone(), t.progress.as_ref().map(|p| p.step)))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn counter_changes_are_picked_up() {
+        let root = Tree::new();
+        let mut a = root.add_child("a");
+        a.init(None, None);
+        let counter = a.counter();
+        let _b = root.add_child("b");
+        let mut changes = Changes::default();
+        root.changes_since(None, &mut changes);
+        root.changes_since(Some(changes.generation), &mut changes);
+
+        counter.inc_by(5);
+        root.changes_since(Some(changes.generation), &mut changes);
+        assert_eq!(names(&changes), vec!["a"]);
+        assert_eq!(changes.updated[0].1.progress.as_ref().map(|p| p.step), Some(5));
+    }
+
+    #[test]
+    fn additional_counter_changes_are_picked_up() {
+        let root = Tree::new();
+        let mut a = root.add_child("a");
+        let bytes = a.add_counter("bytes", None, None);
+        let _b = root.add_child("b");
+        let mut changes = Changes::default();
+        root.changes_since(None, &mut changes);
+        root.changes_since(Some(changes.generation), &mut changes);
+
+        bytes.inc_by(5);
+        root.changes_since(Some(changes.generation), &mut changes);
+        assert_eq!(names(&changes), vec!["a"]);
+        assert_eq!(changes.updated[0].1.counters[0].step, 5);
+    }
+
+    #[test]
+    fn cancellation_requests_are_changes() {
+        let root = Tree::new();
+        let a = root.add_child("a");
+        let _b = root.add_child("b");
+        let mut changes = Changes::default();
+        root.changes_since(None, &mut changes);
+        root.changes_since(Some(changes.generation), &mut changes);
+
+        root.request_cancel(&a.key);
+        root.changes_since(Some(changes.generation), &mut changes);
+        assert_eq!(names(&changes), vec!["a"]);
+        assert!(changes.updated[0].1.cancel_requested);
+    }
+
+    #[test]
+    fn pausing_and_resuming_are_changes() {
+        let root = Tree::new();
+        let mut a = root.add_child("a");
+        a.init(None, None);
+        let _b = root.add_child("b");
+        let mut changes = Changes::default();
+        root.changes_since(None, &mut changes);
+        root.changes_since(Some(changes.generation), &mut changes);
+
+        a.pause();
+        root.changes_since(Some(changes.generation), &mut changes);
+        assert_eq!(names(&changes), vec!["a"]);
+        root.changes_since(Some(changes.generation), &mut changes);
+        a.pause();
+        root.changes_since(Some(changes.generation), &mut changes);
+        assert!(changes.updated.is_empty(), "pausing twice changes nothing");
+        a.resume();
+        root.changes_since(Some(changes.generation), &mut changes);
+        assert_eq!(names(&changes), vec!["a"]);
+    }
+
+    #[test]
+    fn consumers_falling_too_far_behind_receive_complete_snapshots() {
+        let root = Tree::new();
+        let mut changes = Changes::default();
+        root.changes_since(None, &mut changes);
+        let generation = changes.generation;
+
+        for _ in 0..5000 {
+            root.add_child("short-lived");
+        }
+        let _alive = root.add_child("alive");
+        root.changes_since(Some(generation), &mut changes);
+        assert!(changes.is_complete);
+        assert_eq!(names(&changes), vec!["alive"]);
+        assert!(changes.removed.is_empty());
+    }
+}
+
+mod message_sink {
+    use crate::{
+        messages::{Field, Message, MessageLevel},
+        tree::MessageSink,
+        TreeOptions,
+    };
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("prodash-{}-{}.log", std::process::id(), name));
+        remove_files(&path);
+        path
+    }
+
+    fn remove_files(path: &std::path::Path) {
+        for suffix in &["", ".1", ".2"] {
+            std::fs::remove_file(format!("{}{}", path.display(), suffix)).ok();
+        }
+    }
+
+    fn history(root: &crate::Tree, max: usize) -> Vec<Message> {
+        let mut out = Vec::new();
+        root.copy_message_history(&mut out, max);
+        out
+    }
+
+    #[test]
+    fn text_messages_can_be_read_back_beyond_the_buffer_capacity() {
+        let path = temp_path("text");
+        let root = TreeOptions {
+            message_buffer_capacity: 2,
+            message_sink: Some(MessageSink::new(&path)),
+            ..TreeOptions::default()
+        }
+        .create();
+        let mut item = root.add_child("task\twith tab");
+        for n in 0..5 {
+            item.message_with_fields(
+                MessageLevel::Warning,
+                format!("message {}\nwith newline", n),
+                vec![Field::new("n", n), Field::new("a=b", "c=d\\")],
+            );
+        }
+
+        let messages = history(&root, 10);
+        assert_eq!(messages.len(), 5, "all messages are in the file");
+        let message = &messages[4];
+        assert_eq!(message.message, "message 4\nwith newline");
+        assert_eq!(message.origin, "task\twith tab");
+        assert_eq!(message.level, MessageLevel::Warning);
+        assert_eq!(
+            message.fields,
+            vec![Field::new("n", "4"), Field::new("a=b", "c=d\\")],
+            "field values are read back as strings"
+        );
+        let mut buffered = Vec::new();
+        root.copy_messages(&mut buffered);
+        assert_eq!(buffered[1].time, message.time, "time is kept with full precision");
+
+        assert_eq!(
+            history(&root, 3).iter().map(|m| m.message.as_str()).collect::<Vec<_>>(),
+            (2..5)
+                .map(|n| format!("message {}\nwith newline", n))
+                .collect::<Vec<_>>(),
+            "only the most recent messages are returned"
+        );
+        remove_files(&path);
+    }
+
+    #[test]
+    fn files_are_rotated_once_they_grow_too_large() {
+        let path = temp_path("rotation");
+        let root = TreeOptions {
+            message_sink: Some(MessageSink {
+                max_file_size: 200,
+                max_rotated_files: 1,
+                ..MessageSink::new(&path)
+            }),
+            ..TreeOptions::default()
+        }
+        .create();
+        let mut item = root.add_child("task");
+        for n in 0..20 {
+            item.info(format!("message {}", n));
+        }
+
+        assert!(std::fs::metadata(&path).unwrap().len() <= 200);
+        assert!(std::fs::metadata(format!("{}.1", path.display())).is_ok());
+        assert!(
+            std::fs::metadata(format!("{}.2", path.display())).is_err(),
+            "only the configured amount of rotated files is kept"
+        );
+
+        let messages: Vec<_> = history(&root, 100).into_iter().map(|m| m.message).collect();
+        assert!(messages.len() < 20, "the oldest messages were deleted");
+        assert_eq!(
+            messages,
+            (20 - messages.len()..20)
+                .map(|n| format!("message {}", n))
+                .collect::<Vec<_>>(),
+            "messages are read across files in order"
+        );
+        remove_files(&path);
+    }
+
+    #[test]
+    fn only_the_tail_of_large_files_is_read_back_in_order() {
+        let path = temp_path("large");
+        let root = TreeOptions {
+            message_sink: Some(MessageSink::new(&path)),
+            ..TreeOptions::default()
+        }
+        .create();
+        let mut item = root.add_child("task");
+        let filler = "x".repeat(100);
+        for n in 0..3000 {
+            item.info(format!("message {} {}", n, filler));
+        }
+        assert!(std::fs::metadata(&path).unwrap().len() > 3 * 64 * 1024);
+
+        for max in [1, 700, 2500, 5000].iter().copied() {
+            let messages: Vec<_> = history(&root, max).into_iter().map(|m| m.message).collect();
+            assert_eq!(
+                messages,
+                (3000 - max.min(3000)..3000)
+                    .map(|n| format!("message {} {}", n, filler))
+                    .collect::<Vec<_>>(),
+                "lines spanning blocks are read completely"
+            );
+        }
+        remove_files(&path);
+    }
+
+    #[test]
+    fn without_sink_the_history_is_the_message_buffer() {
+        let root = TreeOptions {
+            message_buffer_capacity: 2,
+            ..TreeOptions::default()
+        }
+        .create();
+        let mut item = root.add_child("task");
+        for n in 0..5 {
+            item.info(format!("message {}", n));
+        }
+        assert_eq!(
+            history(&root, 10).into_iter().map(|m| m.message).collect::<Vec<_>>(),
+            vec!["message 3", "message 4"]
+        );
+        assert_eq!(history(&root, 1).len(), 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_lines_retain_field_types() {
+        use crate::{messages::FieldValue, tree::MessageFormat};
+        let path = temp_path("json");
+        let root = TreeOptions {
+            message_sink: Some(MessageSink {
+                format: MessageFormat::JsonLines,
+                ..MessageSink::new(&path)
+            }),
+            ..TreeOptions::default()
+        }
+        .create();
+        root.add_child("task")
+            .message_with_fields(MessageLevel::Failure, "failed", vec![Field::new("code", 42)]);
+
+        let messages = history(&root, 10);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].fields[0].value, FieldValue::Int(42));
+        let mut buffered = Vec::new();
+        root.copy_messages(&mut buffered);
+        assert_eq!(messages, buffered);
+        remove_files(&path);
+    }
+}
+
+mod io {
+    use crate::{
+        messages::Message,
+        progress::{
+            io::{Reader, Writer},
+            ThroughputOnDrop,
+        },
+        Tree,
+    };
+    use std::io::{BufRead, Cursor, Read, Seek, SeekFrom, Write};
+
+    fn progress_of(root: &Tree) -> (usize, Option<usize>) {
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        let progress = snapshot[0].1.progress.as_ref().expect("initialized");
+        (progress.step, progress.done_at)
+    }
+
+    #[test]
+    fn readers_report_bytes_read_and_consumed() -> std::io::Result<()> {
+        let root = Tree::new();
+        let mut reader = Reader::with_len(&b"hello\nworld\n"[..], 12, root.add_child("read"));
+        assert_eq!(progress_of(&root), (0, Some(12)));
+
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        assert_eq!(progress_of(&root), (6, Some(12)), "consumed bytes are counted");
+        reader.read_to_string(&mut line)?;
+        assert_eq!(line, "hello\nworld\n");
+        assert_eq!(progress_of(&root), (12, Some(12)));
+        Ok(())
+    }
+
+    #[test]
+    fn seekable_readers_report_their_position() -> std::io::Result<()> {
+        let root = Tree::new();
+        let mut data = Cursor::new(vec![0u8; 100]);
+        data.set_position(10);
+        let mut reader = Reader::from_seekable(data, root.add_child("read"))?;
+        assert_eq!(reader.inner().position(), 10, "the position is retained");
+        assert_eq!(progress_of(&root), (10, Some(100)));
+
+        reader.read_exact(&mut [0; 20])?;
+        assert_eq!(progress_of(&root), (30, Some(100)));
+        reader.seek(SeekFrom::End(-5))?;
+        assert_eq!(progress_of(&root), (95, Some(100)));
+        Ok(())
+    }
+
+    #[test]
+    fn writers_report_bytes_written_and_their_throughput_when_dropped() -> std::io::Result<()> {
+        let root = Tree::new();
+        let mut writer = Writer::new(Vec::new(), ThroughputOnDrop::new(root.add_child("write")));
+        writer.write_all(b"hello")?;
+        writer.write_all(b" world")?;
+        assert_eq!(progress_of(&root), (11, None));
+
+        let (out, progress) = writer.into_inner();
+        assert_eq!(out, b"hello world");
+        drop(progress);
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        assert!(
+            messages
+                .iter()
+                .map(|m: &Message| m.message.as_str())
+                .any(|m| m.starts_with("done 11")),
+            "a summary is emitted once the transfer ends"
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "futures-lite")]
+    #[test]
+    fn async_readers_and_writers_report_bytes_transferred() -> std::io::Result<()> {
+        use futures_lite::{future::block_on, io::AsyncBufReadExt, io::AsyncWriteExt, AsyncReadExt};
+        let root = Tree::new();
+        let mut reader = Reader::with_len(&b"hello\nworld\n"[..], 12, root.add_child("read"));
+        let mut line = String::new();
+        block_on(AsyncBufReadExt::read_line(&mut reader, &mut line))?;
+        assert_eq!(progress_of(&root), (6, Some(12)), "consumed bytes are counted");
+        let mut rest = String::new();
+        block_on(AsyncReadExt::read_to_string(&mut reader, &mut rest))?;
+        assert_eq!(line + &rest, "hello\nworld\n");
+        assert_eq!(progress_of(&root), (12, Some(12)));
+        drop(reader);
+
+        let mut writer = Writer::new(Vec::new(), root.add_child("write"));
+        block_on(AsyncWriteExt::write_all(&mut writer, b"hello world"))?;
+        assert_eq!(progress_of(&root), (11, None));
+        assert_eq!(writer.inner(), b"hello world");
+        Ok(())
+    }
+}
+
+mod iter {
+    use crate::{
+        progress::iter::IteratorExt,
+        tree::{KeepFinished, Options},
+        Tree,
+    };
+
+    /// A tree keeping tasks around after the adapters owning them are dropped.
+    fn tree() -> Tree {
+        Options {
+            keep_finished: Some(KeepFinished::UntilParentDrops {
+                max_top_level_tasks: 100,
+            }),
+            ..Options::default()
+        }
+        .create()
+    }
+
+    fn progress_and_messages(root: &Tree) -> ((usize, Option<usize>), Vec<String>) {
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        let progress = snapshot[0].1.progress.as_ref().expect("initialized");
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        (
+            (progress.step, progress.done_at),
+            messages.into_iter().map(|m| m.message).collect(),
+        )
+    }
+
+    #[test]
+    fn iterators_with_exact_size_are_bounded_and_show_their_throughput() {
+        let root = tree();
+        let mut items = vec![1, 2, 3].into_iter().with_progress(root.add_child("items"));
+        assert_eq!(progress_and_messages(&root), ((0, Some(3)), vec![]));
+        assert_eq!(items.next(), Some(1));
+        assert_eq!(progress_and_messages(&root).0, (1, Some(3)));
+
+        assert_eq!(items.by_ref().count(), 2);
+        assert_eq!(items.next(), None);
+        let (progress, messages) = progress_and_messages(&root);
+        assert_eq!(progress, (3, Some(3)));
+        assert_eq!(messages.len(), 1, "the summary is shown only once");
+        assert!(messages[0].starts_with("done 3 items"));
+    }
+
+    #[test]
+    fn iterators_can_count_items_by_their_size() {
+        let root = tree();
+        let chunks = vec![vec![0u8; 3], vec![0u8; 5]];
+        let bytes: usize = chunks
+            .into_iter()
+            .with_progress_by(root.add_child("chunks"), |chunk: &Vec<u8>| chunk.len())
+            .map(|chunk| chunk.len())
+            .sum();
+        assert_eq!(bytes, 8);
+        assert_eq!(progress_and_messages(&root).0, (8, None));
+    }
+
+    #[test]
+    fn iterators_without_exact_size_are_unbounded_and_can_finish_with_a_message() {
+        let root = tree();
+        let evens = (0..10)
+            .filter(|n| n % 2 == 0)
+            .with_progress(root.add_child("evens"))
+            .done_message("found all evens")
+            .count();
+        assert_eq!(evens, 5);
+        assert_eq!(
+            progress_and_messages(&root),
+            ((5, None), vec!["found all evens".to_string()])
+        );
+    }
+
+    #[cfg(feature = "futures-core")]
+    #[test]
+    fn streams_drive_progress_like_iterators() {
+        use crate::progress::iter::StreamExt;
+        use futures::StreamExt as _;
+        let root = tree();
+        let items: Vec<_> = futures::executor::block_on(
+            futures::stream::iter(vec![1, 2, 3])
+                .with_progress(root.add_child("items"))
+                .collect(),
+        );
+        assert_eq!(items, vec![1, 2, 3]);
+        let (progress, messages) = progress_and_messages(&root);
+        assert_eq!(progress, (3, Some(3)));
+        assert!(messages[0].starts_with("done 3 items"));
+    }
+
+    #[cfg(feature = "futures-core")]
+    #[test]
+    fn streams_can_count_items_by_their_size() {
+        use crate::progress::iter::StreamExt;
+        use futures::StreamExt as _;
+        let root = tree();
+        let chunks = futures::stream::iter(vec![vec![0u8; 3], vec![0u8; 5]]);
+        let num_chunks = futures::executor::block_on(
+            chunks
+                .with_progress_by(root.add_child("chunks"), |chunk: &Vec<u8>| chunk.len())
+                .count(),
+        );
+        assert_eq!(num_chunks, 2);
+        assert_eq!(progress_and_messages(&root).0, (8, None));
+    }
+}
+
+#[cfg(feature = "rayon")]
+mod par_iter {
+    use crate::{
+        tree::{par_iter::ParallelIteratorExt, KeepFinished, Options},
+        Tree,
+    };
+    use rayon::prelude::*;
+
+    fn steps(root: &Tree) -> Vec<(String, usize, Option<usize>)> {
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        snapshot
+            .into_iter()
+            .map(|(_, task)| {
+                let progress = task.progress.expect("initialized");
+                (task.name, progress.step, progress.done_at)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn all_threads_report_to_a_single_task() {
+        let root = Tree::new();
+        let mut progress = root.add_child("work");
+        let items: Vec<_> = (0..100).into_par_iter().with_progress(&mut progress).collect();
+        assert_eq!(items.len(), 100);
+        assert_eq!(steps(&root), vec![("work".into(), 100, Some(100))]);
+    }
+
+    #[test]
+    fn each_thread_can_report_to_its_own_child() {
+        let root = Options {
+            keep_finished: Some(KeepFinished::UntilParentDrops {
+                max_top_level_tasks: 100,
+            }),
+            ..Options::default()
+        }
+        .create();
+        let mut progress = root.add_child("work");
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().expect("pool");
+        pool.install(|| {
+            (0..100)
+                .into_par_iter()
+                .filter(|n| n % 2 == 0)
+                .with_progress_per_thread(&mut progress)
+                .for_each(|_| {})
+        });
+        let steps = steps(&root);
+        assert_eq!(steps[0], ("work".into(), 50, None), "unindexed iterators are unbounded");
+        assert_eq!(
+            steps[1..].iter().map(|(name, _, _)| name.as_str()).collect::<Vec<_>>(),
+            vec!["thread 1", "thread 2"]
         );
+        assert_eq!(steps[1..].iter().map(|(_, step, _)| step).sum::<usize>(), 50);
+    }
+
+    #[test]
+    fn threads_are_counted_in_the_pool_running_the_iteration() {
+        let root = Options {
+            keep_finished: Some(KeepFinished::UntilParentDrops {
+                max_top_level_tasks: 100,
+            }),
+            ..Options::default()
+        }
+        .create();
+        let mut progress = root.add_child("work");
+        let num_threads = rayon::current_num_threads() + 2;
+        let iter = (0..100).into_par_iter().with_progress_per_thread(&mut progress);
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .expect("pool");
+        pool.install(|| iter.for_each(|_| {}));
+        let steps = steps(&root);
+        assert_eq!(steps[0], ("work".into(), 100, Some(100)));
         assert_eq!(
-            Key::adjacency(&entries, 1),
-            Adjacency(AboveAndBelow, NotFound, NotFound, NotFound)
+            steps[1..].iter().map(|(name, _, _)| name.clone()).collect::<Vec<_>>(),
+            (1..=num_threads).map(|n| format!("thread {}", n)).collect::<Vec<_>>()
         );
         assert_eq!(
-            Key::adjacency(&entries, 2),
-            Adjacency(Above, NotFound, NotFound, NotFound)
+            steps[1..].iter().map(|(_, step, _)| step).sum::<usize>(),
+            100,
+            "items processed by threads beyond the size of the pool creating the iterator are counted as well"
         );
     }
 }
+
+#[cfg(feature = "tracing")]
+mod layer {
+    use crate::{
+        messages::{FieldValue, MessageLevel},
+        tree::layer::ProgressLayer,
+        Tree,
+    };
+    use tracing_subscriber::layer::SubscriberExt;
+
+    /// The level, name and progress of each task.
+    type Tasks = Vec<(usize, String, Option<(usize, Option<usize>)>)>;
+
+    fn tasks(root: &Tree) -> Tasks {
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        snapshot
+            .into_iter()
+            .map(|(key, task)| (key.level(), task.name, task.progress.map(|p| (p.step, p.done_at))))
+            .collect()
+    }
+
+    #[test]
+    fn spans_become_nested_tasks_driven_by_their_fields() {
+        let root = Tree::new();
+        let subscriber = tracing_subscriber::registry().with(ProgressLayer::new(root.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            let outer = tracing::info_span!("outer");
+            let _outer = outer.enter();
+            let inner = tracing::info_span!("inner", total = 10u64, step = tracing::field::Empty);
+            assert_eq!(
+                tasks(&root),
+                vec![(1, "outer".into(), None), (2, "inner".into(), Some((0, Some(10))))]
+            );
+
+            inner.record("step", 4u64);
+            assert_eq!(tasks(&root)[1], (2, "inner".into(), Some((4, Some(10)))));
+            drop(inner);
+            assert_eq!(tasks(&root).len(), 1, "closed spans are removed");
+        });
+    }
+
+    #[test]
+    fn events_become_messages_of_their_span() {
+        let root = Tree::new();
+        let subscriber = tracing_subscriber::registry().with(ProgressLayer::new(root.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("task").in_scope(|| {
+                tracing::warn!(attempt = 2u64, "retrying");
+            });
+            tracing::error!("outside of spans");
+        });
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        assert_eq!(
+            messages
+                .iter()
+                .map(|m| (m.level, m.origin.as_str(), m.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (MessageLevel::Warning, "task", "retrying"),
+                (MessageLevel::Failure, "", "outside of spans")
+            ]
+        );
+        assert_eq!(messages[0].fields[0].key, "attempt");
+        assert!(matches!(messages[0].fields[0].value, FieldValue::Uint(2)));
+    }
+
+    #[test]
+    #[cfg(feature = "progress-tree-log")]
+    fn messages_forwarded_to_log_and_back_are_ignored() {
+        let root = Tree::new();
+        // Only the global subscriber is re-entered by events coming back through `log`, scoped ones are guarded against it.
+        tracing::subscriber::set_global_default(tracing_subscriber::registry().with(ProgressLayer::new(root.clone())))
+            .expect("the only test installing a global subscriber");
+        tracing_log::LogTracer::init().expect("the only test installing a logger");
+        let (done, finished) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            tracing::info_span!("task").in_scope(|| tracing::info!("in span"));
+            tracing::info!("outside of spans");
+            done.send(()).ok();
+        });
+        finished
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("events don't deadlock when they come back through `log`");
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        for expected in &["in span", "outside of spans"] {
+            assert_eq!(
+                messages.iter().filter(|m| m.message == *expected).count(),
+                1,
+                "messages are recorded once"
+            );
+        }
+    }
+}
+
+#[cfg(feature = "progress-tree-log")]
+mod logger {
+    use crate::{
+        messages::{FieldValue, MessageLevel},
+        tree::Logger,
+        Tree,
+    };
+    use log::{Level, LevelFilter, Log, Record};
+
+    fn log(logger: &Logger, level: Level, target: &str, message: &str) {
+        logger.log(
+            &Record::builder()
+                .level(level)
+                .target(target)
+                .args(format_args!("{}", message))
+                .build(),
+        );
+    }
+
+    fn messages(root: &Tree) -> Vec<(MessageLevel, String, String)> {
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        messages.into_iter().map(|m| (m.level, m.origin, m.message)).collect()
+    }
+
+    #[test]
+    fn records_become_messages_filtered_by_level_and_target() {
+        let root = Tree::new();
+        let logger = Logger::new(root.clone())
+            .target_level("noisy", LevelFilter::Off)
+            .target_level("noisy::important", LevelFilter::Warn)
+            .target_level("chatty", LevelFilter::Trace);
+        log(&logger, Level::Error, "app", "failed");
+        log(&logger, Level::Debug, "app", "too detailed");
+        log(&logger, Level::Info, "noisy::io", "ignored");
+        log(&logger, Level::Warn, "noisy::important", "kept");
+        log(&logger, Level::Trace, "chatty", "details");
+        assert_eq!(
+            messages(&root),
+            vec![
+                (MessageLevel::Failure, "app".into(), "failed".into()),
+                (MessageLevel::Warning, "noisy::important".into(), "kept".into()),
+                (MessageLevel::Debug, "chatty".into(), "details".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn key_values_become_fields() {
+        let root = Tree::new();
+        let logger = Logger::new(root.clone());
+        let kvs = [
+            ("attempt", log::kv::Value::from(2u64)),
+            ("path", log::kv::Value::from("a.txt")),
+        ];
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .target("app")
+                .args(format_args!("retrying"))
+                .key_values(&kvs)
+                .build(),
+        );
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        assert_eq!(messages[0].fields[0].key, "attempt");
+        assert!(matches!(messages[0].fields[0].value, FieldValue::Uint(2)));
+        assert_eq!(messages[0].fields[1].value.to_string(), "a.txt");
+    }
+
+    #[test]
+    fn messages_forwarded_by_the_tree_are_ignored() {
+        let root = Tree::new();
+        let logger = Logger::new(root.clone());
+        log(&logger, Level::Info, crate::tree::item::LOG_TARGET, "task → done");
+        assert!(messages(&root).is_empty(), "they are in the tree already");
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serialization {
+    use crate::{
+        messages::Message,
+        progress::{self, Key, RollUp, State, Task},
+        unit::{self, display, Unit},
+        Tree,
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn snapshots_and_messages_roundtrip() {
+        let root = Tree::new();
+        let mut parent = root.add_child("parent");
+        let mut child = parent.add_child("child");
+        child.init(Some(10), Some("files".into()));
+        child.set(3);
+        child.blocked("waiting", None);
+        child.info("hello");
+
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let deserialized: Vec<(Key, Task)> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            deserialized.iter().map(|(k, _)| k.clone()).collect::<Vec<_>>(),
+            snapshot.iter().map(|(k, _)| k.clone()).collect::<Vec<_>>()
+        );
+        let progress = deserialized[1].1.progress.as_ref().expect("child has progress");
+        assert_eq!((progress.step, progress.done_at), (3, Some(10)));
+        assert_eq!(progress.state, State::Blocked("waiting".into(), None));
+        assert_eq!(
+            format!("{}", progress.unit.as_ref().unwrap().display(3, Some(10), None)),
+            "3/10 files",
+            "units are serialized as their label"
+        );
+
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        let deserialized: Vec<Message> = serde_json::from_str(&serde_json::to_string(&messages).unwrap()).unwrap();
+        assert_eq!(deserialized, messages);
+    }
+
+    /// The units of tasks rolled up by count and by fraction.
+    fn rolled_up_units() -> Vec<Unit> {
+        let root = Tree::new();
+        let _parents: Vec<_> = [RollUp::Count, RollUp::Fraction]
+            .iter()
+            .map(|roll_up| {
+                let mut parent = root.add_child("parent");
+                parent.set_roll_up(Some(*roll_up));
+                parent
+            })
+            .collect();
+        let mut snapshot = Vec::new();
+        root.sorted_snapshot(&mut snapshot);
+        progress::roll_up(&mut snapshot);
+        snapshot
+            .into_iter()
+            .filter_map(|(_, task)| task.progress.and_then(|p| p.unit))
+            .collect()
+    }
+
+    #[test]
+    fn built_in_units_keep_their_kind_formatting_and_mode() {
+        let mut units = rolled_up_units();
+        units.push(unit::dynamic_and_mode(
+            unit::Range::new("steps"),
+            display::Mode::with_percentage(),
+        ));
+        #[cfg(feature = "unit-bytes")]
+        units.push(unit::dynamic_and_mode(unit::Bytes, display::Mode::with_throughput()));
+        #[cfg(feature = "unit-duration")]
+        units.push(unit::dynamic_and_mode(
+            unit::Duration,
+            display::Mode::with_throughput().show_before_value(),
+        ));
+        #[cfg(feature = "unit-human")]
+        units.push(unit::dynamic_and_mode(
+            unit::Human::new(unit::human::Formatter::new(), "objects"),
+            display::Mode::with_percentage().and_throughput(),
+        ));
+        assert!(units.iter().all(|unit| unit.as_display_value().builtin().is_some()));
+
+        let throughput = display::Throughput::new(1500, Duration::from_secs(1));
+        for unit in units {
+            let json = serde_json::to_string(&unit).unwrap();
+            let deserialized: Unit = serde_json::from_str(&json).unwrap();
+            assert_eq!(
+                format!("{}", deserialized.display(3000, Some(6000), throughput)),
+                format!("{}", unit.display(3000, Some(6000), throughput)),
+                "{}",
+                json
+            );
+            assert_eq!(serde_json::to_string(&deserialized).unwrap(), json, "it roundtrips");
+        }
+    }
+}
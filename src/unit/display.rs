@@ -6,6 +6,7 @@ use std::fmt::{self, Write};
 
 /// The location at which [`Throughput`] or [`UnitDisplays`][UnitDisplay] should be placed.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum Location {
     BeforeValue,
@@ -33,6 +34,7 @@ impl Throughput {
 
 /// A way to display a [Unit].
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mode {
     location: Location,
     percent: bool,
@@ -18,4 +18,8 @@ impl DisplayValue for Duration {
     fn display_unit(&self, _w: &mut dyn fmt::Write, _value: Step) -> fmt::Result {
         Ok(())
     }
+    #[cfg(feature = "serde")]
+    fn builtin(&self) -> Option<crate::unit::Builtin> {
+        Some(crate::unit::Builtin::Duration)
+    }
 }
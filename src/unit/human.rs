@@ -37,4 +37,8 @@ impl DisplayValue for Human {
     fn display_unit(&self, w: &mut dyn fmt::Write, _value: Step) -> fmt::Result {
         w.write_str(self.name)
     }
+    #[cfg(feature = "serde")]
+    fn builtin(&self) -> Option<crate::unit::Builtin> {
+        Some(crate::unit::Builtin::Human)
+    }
 }
@@ -22,6 +22,8 @@ mod range;
 pub use range::Range;
 
 mod traits;
+#[cfg(feature = "serde")]
+pub use traits::Builtin;
 pub use traits::DisplayValue;
 
 /// Various utilities to display values and units.
@@ -104,7 +106,7 @@ impl Unit {
         current_value: Step,
         upper_bound: Option<Step>,
         throughput: impl Into<Option<display::Throughput>>,
-    ) -> display::UnitDisplay {
+    ) -> display::UnitDisplay<'_> {
         display::UnitDisplay {
             current_value,
             upper_bound,
@@ -123,5 +125,114 @@ impl Unit {
     }
 }
 
+/// Units serialize to their kind, rendered label and display mode, and deserialize into a [dynamic](dynamic()) unit
+/// displaying that label. [Built-in](DisplayValue::builtin()) units format their values as before if the respective
+/// feature is enabled, with [`Human`] using the default formatter.
+#[cfg(feature = "serde")]
+mod serialize {
+    use crate::{
+        progress::Step,
+        unit::{display, dynamic, Builtin, DisplayValue, Unit},
+    };
+    use std::fmt;
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Repr {
+        /// The built-in unit, or `None` for labels and custom units.
+        kind: Option<Builtin>,
+        label: String,
+        mode: Option<display::Mode>,
+    }
+
+    impl serde::Serialize for Unit {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let unit = self.as_display_value();
+            let mut label = String::new();
+            unit.display_unit(&mut label, 0).map_err(serde::ser::Error::custom)?;
+            serde::Serialize::serialize(
+                &Repr {
+                    kind: unit.builtin(),
+                    label,
+                    mode: self.mode,
+                },
+                serializer,
+            )
+        }
+    }
+
+    impl<'de> serde::Deserialize<'de> for Unit {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let Repr { kind, label, mode } = serde::Deserialize::deserialize(deserializer)?;
+            let mut unit = dynamic(Deserialized {
+                inner: kind.map(values_of).unwrap_or_else(|| Box::new(Values)),
+                kind,
+                label,
+            });
+            unit.mode = mode;
+            Ok(unit)
+        }
+    }
+
+    /// Return a unit formatting values like the built-in unit `kind`, or like a label if its feature isn't enabled.
+    fn values_of(kind: Builtin) -> Box<dyn DisplayValue + Send + Sync> {
+        match kind {
+            #[cfg(feature = "unit-bytes")]
+            Builtin::Bytes => Box::new(crate::unit::Bytes),
+            #[cfg(feature = "unit-duration")]
+            Builtin::Duration => Box::new(crate::unit::Duration),
+            #[cfg(feature = "unit-human")]
+            Builtin::Human => Box::new(crate::unit::Human::new(Default::default(), "")),
+            Builtin::Range => Box::new(crate::unit::Range::new("")),
+            Builtin::SubtasksDone => Box::new(crate::progress::SubtasksDone),
+            Builtin::Percentage => Box::new(crate::progress::Percentage),
+            #[allow(unreachable_patterns)]
+            _ => Box::new(Values),
+        }
+    }
+
+    /// Values formatted like those of a label.
+    struct Values;
+
+    impl DisplayValue for Values {
+        fn display_unit(&self, _w: &mut dyn fmt::Write, _value: Step) -> fmt::Result {
+            Ok(())
+        }
+    }
+
+    /// A deserialized unit, formatting values with `inner` and displaying `label` as unit.
+    struct Deserialized {
+        inner: Box<dyn DisplayValue + Send + Sync>,
+        kind: Option<Builtin>,
+        label: String,
+    }
+
+    impl DisplayValue for Deserialized {
+        fn display_current_value(&self, w: &mut dyn fmt::Write, value: Step, upper: Option<Step>) -> fmt::Result {
+            self.inner.display_current_value(w, value, upper)
+        }
+        fn separator(&self, w: &mut dyn fmt::Write, value: Step, upper: Option<Step>) -> fmt::Result {
+            self.inner.separator(w, value, upper)
+        }
+        fn display_upper_bound(&self, w: &mut dyn fmt::Write, upper_bound: Step, value: Step) -> fmt::Result {
+            self.inner.display_upper_bound(w, upper_bound, value)
+        }
+        fn display_unit(&self, w: &mut dyn fmt::Write, _value: Step) -> fmt::Result {
+            w.write_str(&self.label)
+        }
+        fn display_percentage(&self, w: &mut dyn fmt::Write, percentage: f64) -> fmt::Result {
+            self.inner.display_percentage(w, percentage)
+        }
+        fn display_throughput(&self, w: &mut dyn fmt::Write, throughput: display::Throughput) -> fmt::Result {
+            self.inner.display_throughput(w, throughput)
+        }
+        fn fraction_and_time_unit(&self, timespan: std::time::Duration) -> (Option<f64>, &'static str) {
+            self.inner.fraction_and_time_unit(timespan)
+        }
+        fn builtin(&self) -> Option<Builtin> {
+            self.kind
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests;
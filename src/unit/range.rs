@@ -25,4 +25,8 @@ impl DisplayValue for Range {
     fn display_unit(&self, w: &mut dyn fmt::Write, _value: Step) -> fmt::Result {
         w.write_str(self.name)
     }
+    #[cfg(feature = "serde")]
+    fn builtin(&self) -> Option<crate::unit::Builtin> {
+        Some(crate::unit::Builtin::Range)
+    }
 }
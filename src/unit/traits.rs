@@ -1,6 +1,23 @@
 use crate::{progress::Step, unit::display};
 use std::fmt;
 
+/// The units provided by this crate, as returned by [`DisplayValue::builtin()`].
+#[cfg(feature = "serde")]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+#[allow(missing_docs)]
+pub enum Builtin {
+    Bytes,
+    Duration,
+    Human,
+    Range,
+    /// The unit of tasks rolled up with [`RollUp::Count`][crate::progress::RollUp::Count].
+    SubtasksDone,
+    /// The unit of tasks rolled up with [`RollUp::Fraction`][crate::progress::RollUp::Fraction].
+    Percentage,
+}
+
 /// A trait to encapsulate all capabilities needed to display a value with unit within a renderer.
 pub trait DisplayValue {
     /// Display the absolute `value` representing the current progress of an operation and write it to `w`.
@@ -30,6 +47,14 @@ pub trait DisplayValue {
     /// The `value` is provided to add context, even though it is not to be output as part of this method call.
     fn display_unit(&self, w: &mut dyn fmt::Write, value: Step) -> fmt::Result;
 
+    /// Return which of the units provided by this crate this is, or `None` for all other units.
+    ///
+    /// Serialized units use it to keep their formatting, which is why custom units should keep the default.
+    #[cfg(feature = "serde")]
+    fn builtin(&self) -> Option<Builtin> {
+        None
+    }
+
     /// Emit `percentage` to `w`.
     fn display_percentage(&self, w: &mut dyn fmt::Write, percentage: f64) -> fmt::Result {
         w.write_fmt(format_args!("[{}%]", percentage as usize))